@@ -0,0 +1,121 @@
+use cardy::face::Face;
+
+use crate::chart::{HandKind, StrategyChart};
+use crate::strategy::View;
+use crate::Decision;
+
+/// A decision the player made, next to the one basic strategy would have made.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DecisionRecord {
+    pub hand_kind: HandKind,
+    pub dealer_upcard: Face,
+    pub made: Decision,
+    pub correct: Decision,
+    /// Expected winnings given up by making this decision instead of the correct one, in whole units.
+    pub ev_cost: f64,
+}
+
+impl DecisionRecord {
+    pub fn is_correct(&self) -> bool {
+        self.made == self.correct
+    }
+}
+
+/// The same wrong decision made on the same kind of hand, however many times it was made.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Mistake {
+    pub hand_kind: HandKind,
+    pub made: Decision,
+    pub correct: Decision,
+    pub count: usize,
+    /// Expected winnings given up over every time it was made, in whole units.
+    pub ev_cost: f64,
+}
+
+/// Keeps score of a player's decisions against basic strategy over a session.
+pub struct AccuracyTracker {
+    chart: StrategyChart,
+    records: Vec<DecisionRecord>,
+}
+
+impl AccuracyTracker {
+    pub fn new(chart: StrategyChart) -> AccuracyTracker {
+        AccuracyTracker {
+            chart,
+            records: Vec::new(),
+        }
+    }
+
+    /// The chart that decisions are scored against.
+    pub fn chart(&self) -> &StrategyChart {
+        &self.chart
+    }
+
+    /// Scores `made` as the decision for the hand in `view`.
+    pub fn record(&mut self, view: &View, made: Decision) -> DecisionRecord {
+        let cell = self.chart.cell_for(view);
+        let correct = cell.best(&view.legal);
+        let ev = |decision| {
+            cell.ev(decision)
+                .expect("every legal decision has an expected value")
+        };
+
+        let record = DecisionRecord {
+            hand_kind: HandKind::of(view.hand),
+            dealer_upcard: view.dealer_upcard,
+            made,
+            correct,
+            ev_cost: (ev(correct) - ev(made)) * view.bet.as_units(),
+        };
+        self.records.push(record);
+        record
+    }
+
+    pub fn records(&self) -> &[DecisionRecord] {
+        &self.records
+    }
+
+    /// Percent of decisions that matched basic strategy, or `None` before any have been made.
+    pub fn accuracy(&self) -> Option<f64> {
+        if self.records.is_empty() {
+            return None;
+        }
+        let num_correct = self
+            .records
+            .iter()
+            .filter(|record| record.is_correct())
+            .count();
+        Some(num_correct as f64 / self.records.len() as f64 * 100.)
+    }
+
+    /// Expected winnings given up by every mistake, in whole units.
+    pub fn ev_given_away(&self) -> f64 {
+        self.records.iter().map(|record| record.ev_cost).sum()
+    }
+
+    /// Mistakes grouped by the kind of hand they were made on, most frequent (then most costly) first.
+    pub fn mistakes(&self) -> Vec<Mistake> {
+        let mut mistakes: Vec<Mistake> = Vec::new();
+        for record in self.records.iter().filter(|record| !record.is_correct()) {
+            match mistakes.iter_mut().find(|mistake| {
+                mistake.hand_kind == record.hand_kind
+                    && mistake.made == record.made
+                    && mistake.correct == record.correct
+            }) {
+                Some(mistake) => {
+                    mistake.count += 1;
+                    mistake.ev_cost += record.ev_cost;
+                }
+                None => mistakes.push(Mistake {
+                    hand_kind: record.hand_kind,
+                    made: record.made,
+                    correct: record.correct,
+                    count: 1,
+                    ev_cost: record.ev_cost,
+                }),
+            }
+        }
+        mistakes.sort_by(|a, b| b.count.cmp(&a.count).then(b.ev_cost.total_cmp(&a.ev_cost)));
+        mistakes
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::Arc;
+use std::{env, process, thread};
+
+use blackjack::counting::{parse_system, BuiltInSystem, CountingSystem};
+use blackjack::money::Money;
+use blackjack::rules::RuleSet;
+use blackjack::sim::{simulate, BettingScheme, Estimate, SimConfig, SimStrategy};
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, Options::usage());
+            process::exit(2);
+        }
+    };
+    let config = SimConfig {
+        rules: options.rules,
+        seed: options.seed.unwrap_or_else(rand::random),
+        num_rounds: options.num_rounds,
+        num_threads: options
+            .num_threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get())),
+        strategy: options.strategy,
+        betting: options.betting,
+        system: options.count_system,
+    };
+
+    let stats = match simulate(&config) {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("The simulation stopped: {}", e);
+            process::exit(1);
+        }
+    };
+
+    println!(
+        "Played {} rounds with {} strategy and {} (seed {} over {} threads).",
+        stats.num_rounds,
+        config.strategy.name(),
+        config.betting,
+        config.seed,
+        config.num_threads
+    );
+    if let BettingScheme::Spread { .. } = config.betting {
+        println!("Bets were spread by the {} count.", config.system.name());
+    }
+    println!();
+    println!("House edge:   {}", percent(stats.house_edge()));
+    let sd = stats.sd_per_round();
+    println!("SD per hand:  {:.4} ± {:.4} bets", sd.value, sd.margin);
+    println!("Win rate:     {}", percent(stats.win_rate()));
+    println!("Push rate:    {}", percent(stats.push_rate()));
+    println!("Loss rate:    {}", percent(stats.loss_rate()));
+    println!();
+    println!("Intervals are 95% confidence intervals.");
+}
+
+/// Written like `0.512% ± 0.230%`.
+fn percent(estimate: Estimate) -> String {
+    format!(
+        "{:.3}% ± {:.3}%",
+        estimate.value * 100.,
+        estimate.margin * 100.
+    )
+}
+
+/// Settings read from the command line.
+struct Options {
+    rules: RuleSet,
+    /// Seed for every shuffle, which is picked at random when not given.
+    seed: Option<u64>,
+    num_rounds: u64,
+    /// Threads to simulate on, which is every core when not given.
+    num_threads: Option<usize>,
+    strategy: SimStrategy,
+    betting: BettingScheme,
+    /// The system a spread of bets is made by.
+    count_system: Arc<dyn CountingSystem>,
+}
+
+impl Options {
+    const DEFAULT_ROUNDS: u64 = 1_000_000;
+
+    const USAGE: &'static str = "    --rounds <n>           number of rounds to play, at least 2 and a million by default
+    --strategy <name>      play by basic (the default) or mimic-dealer
+    --bet <scheme>         flat:<bet>, like flat:10 (the default), or spread:<unit>:<max units>,
+                           like spread:10:8 to bet a unit per point of the count
+    --count-system <name>  count by hi-lo (the default), ko, hi-opt-ii, omega-ii, zen or wong-halves,
+                           or by custom tags for an ace through a ten, like -1,1,1,1,1,1,0,0,0,-1
+    --threads <n>          number of threads to simulate on, one per core by default
+    --seed <n>             shuffle from this seed to repeat an earlier simulation with as many threads";
+
+    fn usage() -> String {
+        format!(
+            "usage: blackjack-sim [options]\n\n{}\n{}",
+            RuleSet::OPTIONS_USAGE,
+            Options::USAGE
+        )
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut rules = RuleSet::default();
+        let mut seed = None;
+        let mut num_rounds = Options::DEFAULT_ROUNDS;
+        let mut num_threads = None;
+        let mut strategy = SimStrategy::Basic;
+        let mut betting = BettingScheme::Flat(Money::from_units(10));
+        let mut count_system: Arc<dyn CountingSystem> = Arc::new(BuiltInSystem::HiLo);
+
+        while let Some(arg) = args.next() {
+            if rules.parse_option(&arg, &mut args)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--rounds" => {
+                    num_rounds = match args.next().map(|n| n.parse()) {
+                        // the spread of results, and so every interval, takes at least two rounds to estimate
+                        Some(Ok(n)) if n >= 2 => n,
+                        _ => return Err("--rounds expects a number of at least 2".to_string()),
+                    };
+                }
+                "--threads" => {
+                    num_threads = match args.next().map(|n| n.parse()) {
+                        Some(Ok(n)) if n > 0 => Some(n),
+                        _ => return Err("--threads expects a positive number".to_string()),
+                    };
+                }
+                "--strategy" => {
+                    let name = args.next().unwrap_or_default();
+                    strategy = SimStrategy::from_name(&name).ok_or_else(|| {
+                        format!(
+                            "unknown strategy '{}', expected one of: {}",
+                            name,
+                            SimStrategy::NAMES.join(", ")
+                        )
+                    })?;
+                }
+                "--bet" => {
+                    betting = args
+                        .next()
+                        .unwrap_or_default()
+                        .parse()
+                        .map_err(|e| format!("--bet: {}", e))?;
+                }
+                "--count-system" => {
+                    count_system = parse_system(&args.next().unwrap_or_default())
+                        .map_err(|e| format!("--count-system: {}", e))?;
+                }
+                "--seed" => {
+                    seed = match args.next().map(|n| n.parse()) {
+                        Some(Ok(n)) => Some(n),
+                        _ => return Err("--seed expects a whole number".to_string()),
+                    };
+                }
+                _ => return Err(format!("unknown option '{}'", arg)),
+            }
+        }
+
+        Ok(Options {
+            rules,
+            seed,
+            num_rounds,
+            num_threads,
+            strategy,
+            betting,
+            count_system,
+        })
+    }
+}
@@ -0,0 +1,569 @@
+use std::fmt::{Display, Formatter};
+
+use cardy::{face::Face, hand::Hand, holder::Holder};
+use colored::Colorize;
+
+use crate::dealer::{card_prob, DealerHands, DealerTotals, DEALER_BUST, UPCARD_VALS};
+use crate::rules::{HoleCard, Payout, RuleSet, Surrender, DEALER_STAND_TOTAL};
+use crate::strategy::View;
+use crate::{face_val, hand_val, is_pair, Decision, HandValue, BUST_THRESHOLD};
+
+const HIT_COLOR: (u8, u8, u8) = (214, 96, 96);
+const STAND_COLOR: (u8, u8, u8) = (222, 200, 90);
+const DOUBLE_COLOR: (u8, u8, u8) = (96, 170, 96);
+const SPLIT_COLOR: (u8, u8, u8) = (110, 157, 211);
+const SURRENDER_COLOR: (u8, u8, u8) = (200, 200, 200);
+
+pub(crate) fn ratio(payout: Payout) -> f64 {
+    payout.numerator as f64 / payout.denominator as f64
+}
+
+/// The expected change in winnings, per unit bet, of each decision on one hand against one dealer upcard.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ChartCell {
+    pub(crate) hit: f64,
+    pub(crate) stand: f64,
+    pub(crate) double: f64,
+    pub(crate) split: Option<f64>,
+    pub(crate) surrender: Option<f64>,
+}
+
+impl ChartCell {
+    /// The expected change in winnings per unit bet from `decision`, or `None` if it can't be made on this hand.
+    /// Doubling and surrendering are valued as if the hand still has only its first two cards.
+    pub fn ev(&self, decision: Decision) -> Option<f64> {
+        match decision {
+            Decision::Hit => Some(self.hit),
+            Decision::Stand => Some(self.stand),
+            Decision::Double => Some(self.double),
+            Decision::Split => self.split,
+            Decision::Surrender => self.surrender,
+        }
+    }
+
+    /// The `legal` decision with the highest expected value, which is standing if none of them can be valued.
+    pub fn best(&self, legal: &[Decision]) -> Decision {
+        legal
+            .iter()
+            .filter_map(|&decision| self.ev(decision).map(|ev| (decision, ev)))
+            .fold(
+                None,
+                |best: Option<(Decision, f64)>, (decision, ev)| match best {
+                    Some((_, best_ev)) if best_ev >= ev => best,
+                    _ => Some((decision, ev)),
+                },
+            )
+            .map_or(Decision::Stand, |(decision, _)| decision)
+    }
+
+    /// The cell for a decision made before the dealer checks for a blackjack, which happens with chance `blackjack`
+    /// and takes the original bet from anything but a surrender.
+    fn before_peek(&self, blackjack: f64) -> ChartCell {
+        let with_blackjack = |ev: f64| (1. - blackjack) * ev - blackjack;
+        ChartCell {
+            hit: with_blackjack(self.hit),
+            stand: with_blackjack(self.stand),
+            double: with_blackjack(self.double),
+            split: self.split.map(with_blackjack),
+            surrender: self.surrender,
+        }
+    }
+
+    /// The usual chart shorthand, like `H`, `Ds` for doubling or else standing, or `Rh` for surrendering or else hitting.
+    fn code(&self) -> String {
+        let all = [
+            Decision::Hit,
+            Decision::Stand,
+            Decision::Double,
+            Decision::Split,
+            Decision::Surrender,
+        ];
+        let best = self.best(&all);
+        let letter = |decision: Decision| match decision {
+            Decision::Hit => "H",
+            Decision::Stand => "S",
+            Decision::Double => "D",
+            Decision::Split => "P",
+            Decision::Surrender => "R",
+        };
+        match best {
+            Decision::Hit | Decision::Stand | Decision::Split => letter(best).to_string(),
+            Decision::Double | Decision::Surrender => {
+                let otherwise: Vec<Decision> = all
+                    .into_iter()
+                    .filter(|&decision| decision != best && decision != Decision::Double)
+                    .collect();
+                format!(
+                    "{}{}",
+                    letter(best),
+                    letter(self.best(&otherwise)).to_lowercase()
+                )
+            }
+        }
+    }
+}
+
+/// Which row of a chart a hand is read from.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum HandKind {
+    Hard(usize),
+    Soft(usize),
+    /// A pair, by the [`face_val`] of its cards.
+    Pair(usize),
+}
+
+impl HandKind {
+    /// The kind of `hand`, checking for a pair before a soft or hard total.
+    ///
+    /// # Panics
+    /// If the hand is busted or has fewer than two cards.
+    pub fn of(hand: &Hand) -> HandKind {
+        let value = hand_val(hand);
+        assert!(
+            hand.cards().len() >= 2 && !value.is_bust(),
+            "only unbusted hands of two or more cards are charted"
+        );
+
+        if is_pair(hand) {
+            HandKind::Pair(face_val(hand.cards()[0].face))
+        } else if value.is_soft {
+            HandKind::Soft(value.total)
+        } else {
+            HandKind::Hard(value.total)
+        }
+    }
+}
+
+impl Display for HandKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            HandKind::Hard(total) => write!(f, "hard {}", total),
+            HandKind::Soft(total) => write!(f, "soft {}", total),
+            HandKind::Pair(1) => write!(f, "pair of aces"),
+            HandKind::Pair(val) => write!(f, "pair of {}s", val),
+        }
+    }
+}
+
+/// The best total-dependent basic strategy for a rule set: the expected value of every decision on every hand
+/// against every dealer upcard.
+///
+/// Cards are drawn as if from an infinite shoe, the usual basis for a basic strategy chart, so the number of decks
+/// doesn't change it. The dealer is assumed to play by [`DealerStyle::Casino`](crate::rules::DealerStyle::Casino).
+pub struct StrategyChart {
+    rules: RuleSet,
+    /// Hard totals from 4 to 21, each with a column per upcard from ace to ten.
+    hard: Vec<[ChartCell; 10]>,
+    /// Soft totals from 12 to 21.
+    soft: Vec<[ChartCell; 10]>,
+    /// Pairs from aces to tens.
+    pairs: Vec<[ChartCell; 10]>,
+    /// Chance of a dealer blackjack by upcard from ace to ten, when the first decision on a hand is made before the
+    /// peek under early surrender. The cells above are for decisions made once a blackjack has been ruled out.
+    blackjack_before_peek: [f64; 10],
+}
+
+impl StrategyChart {
+    const MIN_HARD: usize = 4;
+    const MIN_SOFT: usize = 12;
+
+    pub fn new(rules: &RuleSet) -> StrategyChart {
+        let mut chart = StrategyChart {
+            rules: *rules,
+            hard: vec![[ChartCell::default(); 10]; BUST_THRESHOLD + 1 - Self::MIN_HARD],
+            soft: vec![[ChartCell::default(); 10]; BUST_THRESHOLD + 1 - Self::MIN_SOFT],
+            pairs: vec![[ChartCell::default(); 10]; 10],
+            blackjack_before_peek: [0.; 10],
+        };
+
+        let dealer = DealerHands::new(rules);
+        let is_early_surrender =
+            rules.surrender == Surrender::Early && rules.hole_card == HoleCard::Peek;
+        for upcard in 1..=10 {
+            let mut evaluator = Evaluator::new(rules, &dealer, upcard);
+            let column = upcard - 1;
+            if is_early_surrender {
+                chart.blackjack_before_peek[column] = dealer.infinite_odds(upcard).blackjack();
+            }
+            for total in Self::MIN_HARD..=BUST_THRESHOLD {
+                chart.hard[total - Self::MIN_HARD][column] = evaluator.cell(total, false, None);
+            }
+            // a soft total counts its ace as 11, which is 10 more than its hard total
+            for total in Self::MIN_SOFT..=BUST_THRESHOLD {
+                chart.soft[total - Self::MIN_SOFT][column] = evaluator.cell(total - 10, true, None);
+            }
+            for val in 1..=10 {
+                chart.pairs[val - 1][column] = evaluator.cell(val * 2, val == 1, Some(val));
+            }
+        }
+        chart
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The cell for `hand` against `upcard`.
+    ///
+    /// # Panics
+    /// If the hand is busted or has fewer than two cards.
+    pub fn cell(&self, hand: &Hand, upcard: Face) -> &ChartCell {
+        let column = face_val(upcard) - 1;
+        match HandKind::of(hand) {
+            HandKind::Pair(val) => &self.pairs[val - 1][column],
+            HandKind::Soft(total) => &self.soft[total - Self::MIN_SOFT][column],
+            HandKind::Hard(total) => &self.hard[total - Self::MIN_HARD][column],
+        }
+    }
+
+    /// The cell for the hand `view` shows, valued before the peek if the dealer has yet to check for a blackjack.
+    pub fn cell_for(&self, view: &View) -> ChartCell {
+        let cell = self.cell(view.hand, view.dealer_upcard);
+        if view.is_before_peek {
+            cell.before_peek(self.blackjack_before_peek[face_val(view.dealer_upcard) - 1])
+        } else {
+            *cell
+        }
+    }
+
+    /// The chart's choice among the legal decisions `view` shows.
+    pub fn best_decision(&self, view: &View) -> Decision {
+        self.cell_for(view).best(&view.legal)
+    }
+
+    /// The chart as comma-separated values, with a row per hand and a column per dealer upcard.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("kind,hand,2,3,4,5,6,7,8,9,10,A\n");
+        for (kind, label, row) in self.rows() {
+            let codes: Vec<String> = UPCARD_VALS
+                .iter()
+                .map(|&upcard| row[upcard - 1].code())
+                .collect();
+            csv.push_str(&format!("{},\"{}\",{}\n", kind, label, codes.join(",")));
+        }
+        csv
+    }
+
+    /// Every row worth printing, as its kind, its label and its cells for the first decision on the hand. Hands that
+    /// are never played, like a hard 21, are left out.
+    fn rows(&self) -> Vec<(&'static str, String, [ChartCell; 10])> {
+        let hard = (5..=20).map(|total| {
+            (
+                "hard",
+                total.to_string(),
+                &self.hard[total - Self::MIN_HARD],
+            )
+        });
+        let soft = (13..=20).map(|total| {
+            (
+                "soft",
+                format!("A,{}", total - 11),
+                &self.soft[total - Self::MIN_SOFT],
+            )
+        });
+        let pairs = (2..=10).chain(1..=1).map(|val| {
+            let card = if val == 1 {
+                "A".to_string()
+            } else {
+                val.to_string()
+            };
+            ("pair", format!("{},{}", card, card), &self.pairs[val - 1])
+        });
+        hard.chain(soft)
+            .chain(pairs)
+            .map(|(kind, label, row)| {
+                let row = std::array::from_fn(|column| {
+                    row[column].before_peek(self.blackjack_before_peek[column])
+                });
+                (kind, label, row)
+            })
+            .collect()
+    }
+}
+
+impl Display for StrategyChart {
+    /// Prints the chart in color, with a section each for hard totals, soft totals and pairs.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header: String = UPCARD_VALS
+            .iter()
+            .map(|&upcard| {
+                let label = if upcard == 1 {
+                    "A".to_string()
+                } else {
+                    upcard.to_string()
+                };
+                format!("{:^4}", label)
+            })
+            .collect();
+
+        let mut last_kind = "";
+        for (kind, label, row) in self.rows() {
+            if kind != last_kind {
+                let title = match kind {
+                    "hard" => "Hard totals",
+                    "soft" => "Soft totals",
+                    _ => "Pairs",
+                };
+                writeln!(f, "\n{}", title.bold())?;
+                writeln!(f, "{:<7}{}", "", header.as_str().bold())?;
+                last_kind = kind;
+            }
+
+            write!(f, "{:<7}", label)?;
+            for &upcard in &UPCARD_VALS {
+                let code = row[upcard - 1].code();
+                let (r, g, b) = match &code[..1] {
+                    "H" => HIT_COLOR,
+                    "S" => STAND_COLOR,
+                    "D" => DOUBLE_COLOR,
+                    "P" => SPLIT_COLOR,
+                    _ => SURRENDER_COLOR,
+                };
+                write!(
+                    f,
+                    "{}",
+                    format!("{:^4}", code).black().on_truecolor(r, g, b)
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(
+            f,
+            "\nH hit, S stand, P split, D double (h/s: or else hit/stand), R surrender (h/s/p: or else hit/stand/split)"
+        )
+    }
+}
+
+/// Works out the expected values of hands against one dealer upcard.
+struct Evaluator<'r> {
+    rules: &'r RuleSet,
+    /// How the dealer finishes when they don't have a blackjack.
+    dealer: DealerTotals,
+    /// Chance that the dealer has a blackjack, when that's still unknown as the player decides.
+    dealer_blackjack: f64,
+    /// Best value of hitting or standing, by hard total and whether the hand holds an ace.
+    hit_or_stand: Vec<[Option<f64>; 2]>,
+}
+
+impl<'r> Evaluator<'r> {
+    fn new(rules: &'r RuleSet, dealer: &DealerHands, upcard: usize) -> Evaluator<'r> {
+        let odds = dealer.infinite_odds(upcard);
+
+        // after a peek the player only acts once the dealer is known not to have a blackjack; a first decision made
+        // before it under early surrender is valued by `ChartCell::before_peek`
+        let is_blackjack_unknown = rules.hole_card == HoleCard::NoHoleCard;
+
+        Evaluator {
+            rules,
+            dealer: odds.without_blackjack().finishes,
+            dealer_blackjack: if is_blackjack_unknown {
+                odds.blackjack()
+            } else {
+                0.
+            },
+            hit_or_stand: vec![[None; 2]; BUST_THRESHOLD + 11],
+        }
+    }
+
+    /// The cell for a two-card hand, which is the pair of `pair_val` if it's a pair.
+    fn cell(&mut self, hard_total: usize, has_ace: bool, pair_val: Option<usize>) -> ChartCell {
+        let value = HandValue::from_hard_total(hard_total, has_ace);
+        let multiplier = self.rules.double_down_multiplier as f64;
+        let is_no_hole_card = self.rules.hole_card == HoleCard::NoHoleCard;
+
+        // what each decision loses to a dealer blackjack that hasn't been ruled out yet
+        let extra_bets_lost = is_no_hole_card && !self.rules.original_bets_only;
+        let double_loss = if extra_bets_lost { multiplier } else { 1. };
+        let split_loss = if extra_bets_lost { 2. } else { 1. };
+
+        let stand = self.stand(value, ratio(self.rules.win_payout), 1.);
+        let hit = self.hit(hard_total, has_ace);
+        let double = self.double(hard_total, has_ace);
+        let split = match pair_val {
+            Some(val) if self.rules.max_split_hands >= 2 => Some(2. * self.split_hand(val, 2)),
+            _ => None,
+        };
+
+        let dealer_blackjack = self.dealer_blackjack;
+        let with_blackjack =
+            |ev: f64, loss: f64| (1. - dealer_blackjack) * ev - dealer_blackjack * loss;
+        ChartCell {
+            hit: with_blackjack(hit, 1.),
+            stand: with_blackjack(stand, 1.),
+            double: with_blackjack(double, double_loss),
+            split: split.map(|ev| with_blackjack(ev, split_loss)),
+            surrender: (self.rules.surrender != Surrender::NotAllowed).then_some(-0.5),
+        }
+    }
+
+    /// Value of standing on `value`, winning `win` or losing `loss`.
+    fn stand(&self, value: HandValue, win: f64, loss: f64) -> f64 {
+        if value.is_bust() {
+            return -loss;
+        }
+        let mut ev = self.dealer[DEALER_BUST] * win;
+        for (index, chance) in self.dealer[..DEALER_BUST].iter().enumerate() {
+            let dealer_total = DEALER_STAND_TOTAL + index;
+            if dealer_total < value.total {
+                ev += chance * win;
+            } else if dealer_total > value.total {
+                ev -= chance * loss;
+            }
+        }
+        ev
+    }
+
+    /// Value of taking a card and then playing on as well as possible by hitting or standing.
+    fn hit(&mut self, hard_total: usize, has_ace: bool) -> f64 {
+        (1..=10)
+            .map(|val| {
+                let (hard_total, has_ace) = (hard_total + val, has_ace || val == 1);
+                let ev = if HandValue::from_hard_total(hard_total, has_ace).is_bust() {
+                    -1.
+                } else {
+                    self.hit_or_stand(hard_total, has_ace)
+                };
+                card_prob(val) * ev
+            })
+            .sum()
+    }
+
+    fn hit_or_stand(&mut self, hard_total: usize, has_ace: bool) -> f64 {
+        if let Some(ev) = self.hit_or_stand[hard_total][has_ace as usize] {
+            return ev;
+        }
+        let stand = self.stand(
+            HandValue::from_hard_total(hard_total, has_ace),
+            ratio(self.rules.win_payout),
+            1.,
+        );
+        let ev = stand.max(self.hit(hard_total, has_ace));
+        self.hit_or_stand[hard_total][has_ace as usize] = Some(ev);
+        ev
+    }
+
+    /// Value of doubling the wager and taking exactly one more card.
+    fn double(&self, hard_total: usize, has_ace: bool) -> f64 {
+        let multiplier = self.rules.double_down_multiplier as f64;
+        let win = ratio(self.rules.double_down_payout) * multiplier;
+        (1..=10)
+            .map(|val| {
+                let value = HandValue::from_hard_total(hard_total + val, has_ace || val == 1);
+                card_prob(val) * self.stand(value, win, multiplier)
+            })
+            .sum()
+    }
+
+    /// Value of one of the hands made by splitting a pair of `val`, with `num_hands` hands held after the split.
+    /// Each re-split is valued as though the other hands don't affect it.
+    fn split_hand(&mut self, val: usize, num_hands: usize) -> f64 {
+        let win = ratio(self.rules.win_payout);
+        (1..=10)
+            .map(|drawn| {
+                let (hard_total, has_ace) = (val + drawn, val == 1 || drawn == 1);
+                let value = HandValue::from_hard_total(hard_total, has_ace);
+                let played = if value.total == BUST_THRESHOLD && self.rules.split_blackjack_counts {
+                    ratio(self.rules.blackjack_payout)
+                } else if value.total == BUST_THRESHOLD
+                    || (val == 1 && self.rules.split_aces_one_card)
+                {
+                    self.stand(value, win, 1.)
+                } else {
+                    self.stand(value, win, 1.)
+                        .max(self.hit(hard_total, has_ace))
+                        .max(self.double(hard_total, has_ace))
+                };
+
+                let can_resplit = drawn == val
+                    && num_hands < self.rules.max_split_hands
+                    && !(val == 1 && self.rules.split_aces_one_card);
+                let ev = if can_resplit {
+                    played.max(2. * self.split_hand(val, num_hands + 1))
+                } else {
+                    played
+                };
+                card_prob(drawn) * ev
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_card;
+
+    const EVERY_DECISION: [Decision; 5] = [
+        Decision::Hit,
+        Decision::Stand,
+        Decision::Double,
+        Decision::Split,
+        Decision::Surrender,
+    ];
+
+    fn best(chart: &StrategyChart, faces: &[Face], upcard: Face) -> Decision {
+        let mut hand = Hand::new();
+        for &face in faces {
+            hand.push_card(test_card(face));
+        }
+        chart.cell(&hand, upcard).best(&EVERY_DECISION)
+    }
+
+    #[test]
+    fn vegas_strip_chart_matches_basic_strategy() {
+        let chart = StrategyChart::new(&RuleSet::vegas_strip());
+        assert_eq!(
+            best(&chart, &[Face::Ten, Face::Six], Face::Ten),
+            Decision::Surrender
+        );
+        assert_eq!(
+            best(&chart, &[Face::Six, Face::Five], Face::Ace),
+            Decision::Hit
+        );
+        assert_eq!(
+            best(&chart, &[Face::Eight, Face::Eight], Face::Ten),
+            Decision::Split
+        );
+        assert_eq!(
+            best(&chart, &[Face::Ace, Face::Seven], Face::Nine),
+            Decision::Hit
+        );
+    }
+
+    #[test]
+    fn only_the_first_early_surrender_decision_risks_a_blackjack() {
+        let rules = RuleSet {
+            surrender: Surrender::Early,
+            ..RuleSet::default()
+        };
+        let chart = StrategyChart::new(&rules);
+        let mut hand = Hand::new();
+        for face in [Face::Ten, Face::Six] {
+            hand.push_card(test_card(face));
+        }
+        let view = |is_before_peek| View {
+            hand: &hand,
+            dealer_upcard: Face::Ace,
+            bet: crate::money::Money::from_units(10),
+            legal: EVERY_DECISION.to_vec(),
+            is_before_peek,
+            opposing_total: None,
+        };
+
+        let before = chart.cell_for(&view(true));
+        let after = chart.cell_for(&view(false));
+        assert!(before.stand < after.stand);
+        assert_eq!(before.surrender, after.surrender);
+        assert_eq!(chart.best_decision(&view(true)), Decision::Surrender);
+    }
+
+    #[test]
+    fn hitting_soft_17_doubles_11_against_an_ace() {
+        let chart = StrategyChart::new(&RuleSet::default());
+        assert_eq!(
+            best(&chart, &[Face::Six, Face::Five], Face::Ace),
+            Decision::Double
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use cardy::face::Face;
+
+use crate::engine::RoundObserver;
+use crate::event::GameEvent;
+use crate::face_val;
+
+pub const CARDS_PER_DECK: usize = 52;
+
+/// Every card value, from ace to ten, in the order tag tables are written.
+const CARD_VALS: [usize; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+/// Decks left to be dealt, going by the cards left in the shoe. Never less than half a deck,
+/// since counters don't estimate any finer than that.
+pub fn decks_remaining(undealt_cards: usize) -> f64 {
+    (undealt_cards as f64 / CARDS_PER_DECK as f64).max(0.5)
+}
+
+/// A way of counting cards: a tag added to the running count for each card seen.
+pub trait CountingSystem: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn tag(&self, face: Face) -> f64;
+
+    /// Whether a full deck's tags add up to zero. Only a balanced system's running count is meant to be
+    /// converted to a true count.
+    fn is_balanced(&self) -> bool {
+        let deck_total: f64 = CARD_VALS
+            .iter()
+            .map(|&val| self.tag(face_of_val(val)) * if val == 10 { 16. } else { 4. })
+            .sum();
+        deck_total.abs() < f64::EPSILON
+    }
+
+    /// The running count at the start of a shoe of `num_decks`, which unbalanced systems use to line up their
+    /// key counts. Zero by default.
+    fn initial_running_count(&self, _num_decks: usize) -> f64 {
+        0.
+    }
+}
+
+/// Any face with the value `val`, for looking up tags by value.
+fn face_of_val(val: usize) -> Face {
+    match val {
+        1 => Face::Ace,
+        2 => Face::Two,
+        3 => Face::Three,
+        4 => Face::Four,
+        5 => Face::Five,
+        6 => Face::Six,
+        7 => Face::Seven,
+        8 => Face::Eight,
+        9 => Face::Nine,
+        _ => Face::Ten,
+    }
+}
+
+/// The counting systems that come with the game.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BuiltInSystem {
+    HiLo,
+    /// Knock-Out, an unbalanced system that counts sevens as low cards.
+    Ko,
+    HiOptII,
+    OmegaII,
+    Zen,
+    /// Wong Halves, which tags some cards by half a point.
+    WongHalves,
+}
+
+impl BuiltInSystem {
+    /// Names accepted by [`BuiltInSystem::from_name`].
+    pub const NAMES: [&'static str; 6] =
+        ["hi-lo", "ko", "hi-opt-ii", "omega-ii", "zen", "wong-halves"];
+
+    pub fn from_name(name: &str) -> Option<BuiltInSystem> {
+        match name {
+            "hi-lo" => Some(BuiltInSystem::HiLo),
+            "ko" => Some(BuiltInSystem::Ko),
+            "hi-opt-ii" => Some(BuiltInSystem::HiOptII),
+            "omega-ii" => Some(BuiltInSystem::OmegaII),
+            "zen" => Some(BuiltInSystem::Zen),
+            "wong-halves" => Some(BuiltInSystem::WongHalves),
+            _ => None,
+        }
+    }
+
+    /// Tags from ace to ten.
+    fn tags(&self) -> [f64; 10] {
+        match *self {
+            BuiltInSystem::HiLo => [-1., 1., 1., 1., 1., 1., 0., 0., 0., -1.],
+            BuiltInSystem::Ko => [-1., 1., 1., 1., 1., 1., 1., 0., 0., -1.],
+            BuiltInSystem::HiOptII => [0., 1., 1., 2., 2., 1., 1., 0., 0., -2.],
+            BuiltInSystem::OmegaII => [0., 1., 1., 2., 2., 2., 1., 0., -1., -2.],
+            BuiltInSystem::Zen => [-1., 1., 1., 2., 2., 2., 1., 0., 0., -2.],
+            BuiltInSystem::WongHalves => [-1., 0.5, 1., 1., 1.5, 1., 0.5, 0., -0.5, -1.],
+        }
+    }
+}
+
+impl CountingSystem for BuiltInSystem {
+    fn name(&self) -> &str {
+        match *self {
+            BuiltInSystem::HiLo => "Hi-Lo",
+            BuiltInSystem::Ko => "KO",
+            BuiltInSystem::HiOptII => "Hi-Opt II",
+            BuiltInSystem::OmegaII => "Omega II",
+            BuiltInSystem::Zen => "Zen",
+            BuiltInSystem::WongHalves => "Wong Halves",
+        }
+    }
+
+    fn tag(&self, face: Face) -> f64 {
+        self.tags()[face_val(face) - 1]
+    }
+
+    /// KO starts at 4 less than 4 per deck, so that its pivot lands on +4 whatever the number of decks.
+    fn initial_running_count(&self, num_decks: usize) -> f64 {
+        match *self {
+            BuiltInSystem::Ko => 4. - 4. * num_decks as f64,
+            _ => 0.,
+        }
+    }
+}
+
+/// A counting system made up by the player, with a tag for each card value.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TagTable {
+    /// Tags from ace to ten.
+    tags: [f64; 10],
+}
+
+impl TagTable {
+    pub fn new(tags: [f64; 10]) -> TagTable {
+        TagTable { tags }
+    }
+}
+
+impl CountingSystem for TagTable {
+    fn name(&self) -> &str {
+        "Custom"
+    }
+
+    fn tag(&self, face: Face) -> f64 {
+        self.tags[face_val(face) - 1]
+    }
+}
+
+impl Display for TagTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let tags: Vec<String> = self.tags.iter().map(|tag| tag.to_string()).collect();
+        write!(f, "{}", tags.join(","))
+    }
+}
+
+impl FromStr for TagTable {
+    type Err = String;
+
+    /// Parses ten comma-separated tags for an ace through a ten, like `-1,1,1,1,1,1,0,0,0,-1` for Hi-Lo.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tags: Vec<f64> = s
+            .split(',')
+            .map(|tag| {
+                tag.trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' isn't a number", tag))
+            })
+            .collect::<Result<_, _>>()?;
+        let tags: [f64; 10] = tags.try_into().map_err(|tags: Vec<f64>| {
+            format!(
+                "expected 10 tags for an ace through a ten, but got {}",
+                tags.len()
+            )
+        })?;
+        Ok(TagTable::new(tags))
+    }
+}
+
+/// Looks up a built-in system by one of its [`BuiltInSystem::NAMES`], or else parses a custom [`TagTable`].
+pub fn parse_system(s: &str) -> Result<Arc<dyn CountingSystem>, String> {
+    if let Some(system) = BuiltInSystem::from_name(s) {
+        return Ok(Arc::new(system));
+    }
+    s.parse::<TagTable>()
+        .map(|table| Arc::new(table) as Arc<dyn CountingSystem>)
+        .map_err(|e| {
+            format!(
+                "'{}' is neither one of {} nor a custom tag table: {}",
+                s,
+                BuiltInSystem::NAMES.join(", "),
+                e
+            )
+        })
+}
+
+/// The count of a shoe under some counting system, kept by following a table's events the way a player watching
+/// the cards would. Face-down cards aren't counted until they're turned over, and a burned card is never seen at all.
+#[derive(Clone)]
+pub struct RunningCount {
+    system: Arc<dyn CountingSystem>,
+    num_decks: usize,
+    running: f64,
+    /// Sum of the tags counted since the round started, for cards that stay on the table when the discards are
+    /// shuffled in.
+    in_play: f64,
+}
+
+impl RunningCount {
+    /// A count for a freshly shuffled shoe of `num_decks`.
+    pub fn new(system: Arc<dyn CountingSystem>, num_decks: usize) -> RunningCount {
+        RunningCount {
+            running: system.initial_running_count(num_decks),
+            in_play: 0.,
+            system,
+            num_decks,
+        }
+    }
+
+    pub fn system(&self) -> &dyn CountingSystem {
+        self.system.as_ref()
+    }
+
+    pub fn running(&self) -> f64 {
+        self.running
+    }
+
+    /// The running count per deck left in the shoe. This is only meaningful for a balanced system.
+    pub fn true_count(&self, undealt_cards: usize) -> f64 {
+        self.running / decks_remaining(undealt_cards)
+    }
+
+    pub fn observe(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::Reshuffled => {
+                self.running = self.system.initial_running_count(self.num_decks)
+            }
+            // shuffling in the discards mixes counted cards back in, but the ones on the table stay out of the shoe
+            GameEvent::DiscardsShuffledIn => {
+                self.running = self.system.initial_running_count(self.num_decks) + self.in_play
+            }
+            GameEvent::RoundStarted { .. } => self.in_play = 0.,
+            GameEvent::CardDealt {
+                card,
+                is_face_down: false,
+                ..
+            }
+            | GameEvent::HoleCardRevealed { card } => {
+                let tag = self.system.tag(card.face);
+                self.running += tag;
+                self.in_play += tag;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl RoundObserver for RunningCount {
+    fn observe(&mut self, event: &GameEvent) {
+        RunningCount::observe(self, event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Seat;
+    use crate::money::Money;
+    use crate::test_card;
+
+    fn dealt(face: Face) -> GameEvent {
+        GameEvent::CardDealt {
+            seat: Seat::Player(0),
+            card: test_card(face),
+            is_face_down: false,
+        }
+    }
+
+    #[test]
+    fn cards_on_the_table_stay_counted_when_the_discards_are_shuffled_in() {
+        let mut count = RunningCount::new(Arc::new(BuiltInSystem::HiLo), 1);
+        count.observe(&dealt(Face::Five));
+        count.observe(&GameEvent::RoundStarted {
+            bet: Money::from_units(10),
+        });
+        count.observe(&dealt(Face::Two));
+        count.observe(&dealt(Face::Three));
+        assert_eq!(count.running(), 3.);
+
+        count.observe(&GameEvent::DiscardsShuffledIn);
+        assert_eq!(count.running(), 2.);
+        count.observe(&GameEvent::Reshuffled);
+        assert_eq!(count.running(), 0.);
+    }
+
+    #[test]
+    fn ko_starts_below_zero() {
+        let count = RunningCount::new(Arc::new(BuiltInSystem::Ko), 6);
+        assert_eq!(count.running(), -20.);
+    }
+}
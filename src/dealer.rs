@@ -0,0 +1,368 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use colored::Colorize;
+
+use crate::rules::{RuleSet, DEALER_STAND_TOTAL};
+use crate::shoe::ShoeComposition;
+use crate::{HandValue, BUST_THRESHOLD};
+
+/// Dealer upcards by [`face_val`](crate::face_val), in the order tables are printed.
+pub(crate) const UPCARD_VALS: [usize; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 1];
+
+/// Chance of drawing a card of value `val`, from 1 for an ace to 10, treating the shoe as infinite.
+pub(crate) fn card_prob(val: usize) -> f64 {
+    if val == 10 {
+        4. / 13.
+    } else {
+        1. / 13.
+    }
+}
+
+/// Chances of the dealer finishing on each total from 17 to 21, followed by the chance of busting.
+pub(crate) type DealerTotals = [f64; 6];
+
+pub(crate) const DEALER_BUST: usize = 5;
+
+/// Chances of each way the dealer's hand can finish from one upcard. Together they add up to 1.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DealerOdds {
+    /// Chances of finishing on each total from 17 to 21 without a blackjack, then of busting.
+    pub(crate) finishes: DealerTotals,
+    pub(crate) blackjack: f64,
+}
+
+impl DealerOdds {
+    /// Chance of finishing on `total` other than by a blackjack.
+    ///
+    /// # Panics
+    /// If `total` isn't from 17 to 21.
+    pub fn total(&self, total: usize) -> f64 {
+        assert!(
+            (DEALER_STAND_TOTAL..=BUST_THRESHOLD).contains(&total),
+            "the dealer only finishes on totals from 17 to 21"
+        );
+        self.finishes[total - DEALER_STAND_TOTAL]
+    }
+
+    pub fn blackjack(&self) -> f64 {
+        self.blackjack
+    }
+
+    pub fn bust(&self) -> f64 {
+        self.finishes[DEALER_BUST]
+    }
+
+    /// The odds for a dealer known not to have a blackjack, such as after a peek.
+    pub fn without_blackjack(&self) -> DealerOdds {
+        let no_blackjack = 1. - self.blackjack;
+        DealerOdds {
+            finishes: self.finishes.map(|chance| chance / no_blackjack),
+            blackjack: 0.,
+        }
+    }
+}
+
+/// Every way the dealer can play out a hand from each upcard, drawing by [`RuleSet::dealer_must_hit`], from which
+/// their odds are worked out for any shoe. The dealer is assumed to play by
+/// [`DealerStyle::Casino`](crate::rules::DealerStyle::Casino).
+pub struct DealerHands {
+    rules: RuleSet,
+    /// The ways to play out each upcard, from ace to ten.
+    draws: Vec<Vec<DealerDraw>>,
+}
+
+impl DealerHands {
+    pub fn new(rules: &RuleSet) -> DealerHands {
+        DealerHands {
+            rules: *rules,
+            draws: (1..=10)
+                .map(|upcard| DealerDraw::all(rules, upcard))
+                .collect(),
+        }
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// How the dealer finishes showing `upcard`, from 1 for an ace to 10, with the hole card and every card after it
+    /// drawn from `shoe`. The upcard should already be out of `shoe`.
+    pub fn odds(&self, upcard: usize, shoe: &ShoeComposition) -> DealerOdds {
+        let ways = DrawWays::new(shoe);
+        self.add_up(upcard, |draw| draw.chance(&ways))
+    }
+
+    /// How the dealer finishes showing `upcard`, drawing from an infinite shoe as basic strategy assumes.
+    pub fn infinite_odds(&self, upcard: usize) -> DealerOdds {
+        self.add_up(upcard, DealerDraw::infinite_chance)
+    }
+
+    fn add_up(&self, upcard: usize, chance: impl Fn(&DealerDraw) -> f64) -> DealerOdds {
+        let mut odds = DealerOdds {
+            finishes: [0.; 6],
+            blackjack: 0.,
+        };
+        for draw in &self.draws[upcard - 1] {
+            match draw.finish {
+                Some(finish) => odds.finishes[finish] += chance(draw),
+                None => odds.blackjack += chance(draw),
+            }
+        }
+        odds
+    }
+}
+
+/// The dealer's odds for every upcard dealt from one shoe, which prints as a table.
+pub struct DealerOddsTable {
+    rules: RuleSet,
+    /// Odds by upcard from ace to ten, or `None` for an upcard that the shoe has none of.
+    odds: Vec<Option<DealerOdds>>,
+}
+
+impl DealerOddsTable {
+    /// The odds for each upcard dealt from `shoe`, with the upcard taken out of it first. Fails if the shoe could run
+    /// out before the dealer finishes their hand on one of the upcards, since that upcard's odds wouldn't add up.
+    pub fn new(hands: &DealerHands, shoe: &ShoeComposition) -> Result<DealerOddsTable, String> {
+        let odds = (1..=10)
+            .map(|upcard| {
+                let Some(mut rest) = shoe.without_val(upcard) else {
+                    return Ok(None);
+                };
+                let odds = hands.odds(upcard, &rest);
+                if !rest.draw_lowest(upcard, DEALER_STAND_TOTAL) {
+                    return Err(
+                        "the shoe could run out before the dealer's hand is played out".to_string(),
+                    );
+                }
+                Ok(Some(odds))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(DealerOddsTable {
+            rules: *hands.rules(),
+            odds,
+        })
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The odds for `upcard`, from 1 for an ace to 10, or `None` if the shoe has none of it.
+    pub fn odds(&self, upcard: usize) -> Option<&DealerOdds> {
+        self.odds[upcard - 1].as_ref()
+    }
+}
+
+impl Display for DealerOddsTable {
+    /// Prints a row of percentages for each upcard, with a column for each way the dealer can finish.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header: String = ["17", "18", "19", "20", "21", "BJ", "Bust"]
+            .iter()
+            .map(|label| format!("{:>8}", label))
+            .collect();
+        writeln!(f, "{:<8}{}", "Upcard".bold(), header.as_str().bold())?;
+
+        for &upcard in &UPCARD_VALS {
+            let label = if upcard == 1 {
+                "A".to_string()
+            } else {
+                upcard.to_string()
+            };
+            write!(f, "{:<8}", label)?;
+            match self.odds(upcard) {
+                Some(odds) => {
+                    for chance in odds.finishes[..DEALER_BUST]
+                        .iter()
+                        .chain([&odds.blackjack, &odds.finishes[DEALER_BUST]])
+                    {
+                        write!(f, "{:>7.2}%", chance * 100.)?;
+                    }
+                    writeln!(f)?;
+                }
+                None => writeln!(f, "{:>8}", "-")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Most cards the dealer could ever draw after their upcard: each one adds at least 1 to a hard total that starts at
+/// 1 or more, and the dealer never draws on a hard 17.
+const MAX_DEALER_DRAWS: usize = DEALER_STAND_TOTAL;
+
+/// One way the dealer's hand can play out from their upcard: the cards drawn after it, hole card included, and
+/// how many of the orders they could come in are played out in full.
+struct DealerDraw {
+    /// The value of each card drawn, from 1 for an ace to 10, and how many of it were drawn.
+    drawn: Vec<(usize, usize)>,
+    num_drawn: usize,
+    num_orders: f64,
+    /// The index of the total finished on in [`DealerTotals`], or `None` for a blackjack.
+    finish: Option<usize>,
+}
+
+impl DealerDraw {
+    /// Every way the dealer can play out a hand from `upcard`. Since drawing the same cards in any order is as likely
+    /// as any other, grouping the orders together leaves far fewer ways to add up for each composition.
+    fn all(rules: &RuleSet, upcard: usize) -> Vec<DealerDraw> {
+        // a sorted map keeps the order they're added up in, and so the sums themselves, the same every time
+        let mut draws = BTreeMap::new();
+        DealerDraw::walk(rules, upcard, upcard == 1, &mut [0; 10], &mut draws);
+        draws
+            .into_iter()
+            .map(|(drawn, (num_orders, finish))| DealerDraw {
+                drawn: (1..=10)
+                    .map(|val| (val, drawn[val - 1]))
+                    .filter(|&(_, num)| num > 0)
+                    .collect(),
+                num_drawn: drawn.iter().sum(),
+                num_orders,
+                finish,
+            })
+            .collect()
+    }
+
+    fn walk(
+        rules: &RuleSet,
+        hard_total: usize,
+        has_ace: bool,
+        drawn: &mut [usize; 10],
+        draws: &mut BTreeMap<[usize; 10], (f64, Option<usize>)>,
+    ) {
+        let value = HandValue::from_hard_total(hard_total, has_ace);
+        let num_drawn: usize = drawn.iter().sum();
+        let finish = if num_drawn == 1 && value.total == BUST_THRESHOLD {
+            None
+        } else if value.is_bust() {
+            Some(DEALER_BUST)
+        } else if !rules.dealer_must_hit(value) {
+            Some(value.total - DEALER_STAND_TOTAL)
+        } else {
+            for val in 1..=10 {
+                drawn[val - 1] += 1;
+                DealerDraw::walk(rules, hard_total + val, has_ace || val == 1, drawn, draws);
+                drawn[val - 1] -= 1;
+            }
+            return;
+        };
+        draws.entry(*drawn).or_insert((0., finish)).0 += 1.;
+    }
+
+    /// Chance of drawing these cards in one of the orders that plays out in full, given the ways of drawing each
+    /// number of cards from a composition. It's zero if the composition doesn't hold enough cards.
+    fn chance(&self, ways: &DrawWays) -> f64 {
+        if ways.of_any[self.num_drawn] == 0. {
+            return 0.;
+        }
+        let ways_drawn: f64 = self
+            .drawn
+            .iter()
+            .map(|&(val, num)| ways.of_val[val - 1][num])
+            .product();
+        self.num_orders * ways_drawn / ways.of_any[self.num_drawn]
+    }
+
+    /// Chance of drawing these cards in one of the orders that plays out in full from an infinite shoe.
+    fn infinite_chance(&self) -> f64 {
+        self.drawn
+            .iter()
+            .map(|&(val, num)| card_prob(val).powi(num as i32))
+            .product::<f64>()
+            * self.num_orders
+    }
+}
+
+/// The number of ordered ways to draw some number of cards from a composition, up to as many as the dealer could.
+struct DrawWays {
+    /// Ways to draw a number of cards that are all of one value, by value from ace to ten.
+    of_val: [[f64; MAX_DEALER_DRAWS + 1]; 10],
+    /// Ways to draw a number of cards of any value.
+    of_any: [f64; MAX_DEALER_DRAWS + 1],
+}
+
+impl DrawWays {
+    fn new(shoe: &ShoeComposition) -> DrawWays {
+        // each is a falling factorial, like n * (n - 1) * (n - 2) ways to draw 3 of n cards
+        let falling = |count: usize| {
+            let mut ways = [1.; MAX_DEALER_DRAWS + 1];
+            for num in 1..=MAX_DEALER_DRAWS {
+                ways[num] = ways[num - 1] * count.saturating_sub(num - 1) as f64;
+            }
+            ways
+        };
+        DrawWays {
+            of_val: std::array::from_fn(|index| falling(shoe.count(index + 1))),
+            of_any: falling(shoe.total()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Soft17;
+
+    fn total_chance(odds: &DealerOdds) -> f64 {
+        odds.finishes.iter().sum::<f64>() + odds.blackjack
+    }
+
+    #[test]
+    fn each_upcard_finishes_somehow() {
+        for rules in [RuleSet::default(), RuleSet::vegas_strip()] {
+            let hands = DealerHands::new(&rules);
+            let table =
+                DealerOddsTable::new(&hands, &ShoeComposition::full(rules.num_decks)).unwrap();
+            for upcard in 1..=10 {
+                let odds = table.odds[upcard - 1].expect("a full shoe holds every upcard");
+                assert!((total_chance(&odds) - 1.).abs() < 1e-9, "upcard {}", upcard);
+                assert!((total_chance(&hands.infinite_odds(upcard)) - 1.).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn only_aces_and_tens_make_a_blackjack() {
+        let hands = DealerHands::new(&RuleSet::default());
+        for upcard in 2..=9 {
+            assert_eq!(hands.infinite_odds(upcard).blackjack(), 0.);
+        }
+        assert!((hands.infinite_odds(1).blackjack() - 4. / 13.).abs() < 1e-12);
+        assert!((hands.infinite_odds(10).blackjack() - 1. / 13.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hitting_soft_17_busts_more_often() {
+        let stands = DealerHands::new(&RuleSet {
+            soft_17: Soft17::Stand,
+            ..RuleSet::default()
+        });
+        let hits = DealerHands::new(&RuleSet {
+            soft_17: Soft17::Hit,
+            ..RuleSet::default()
+        });
+        assert!(hits.infinite_odds(6).bust() > stands.infinite_odds(6).bust());
+        assert!(hits.infinite_odds(6).total(17) < stands.infinite_odds(6).total(17));
+    }
+
+    #[test]
+    fn upcards_the_shoe_lacks_have_no_odds() {
+        let shoe: ShoeComposition = "0,0,0,0,0,0,0,0,0,5".parse().unwrap();
+        let table = DealerOddsTable::new(&DealerHands::new(&RuleSet::default()), &shoe).unwrap();
+        assert_eq!(table.odds(1), None);
+        assert_eq!(table.odds(10).map(|odds| odds.total(20)), Some(1.));
+    }
+
+    #[test]
+    fn rejects_shoes_the_dealer_could_run_out_of() {
+        let hands = DealerHands::new(&RuleSet::default());
+        for shoe in [
+            "0,1,0,0,0,0,0,0,0,0",
+            "0,4,0,0,0,0,0,0,0,0",
+            "0,0,0,0,0,0,0,0,0,1",
+        ] {
+            let shoe: ShoeComposition = shoe.parse().unwrap();
+            assert!(DealerOddsTable::new(&hands, &shoe).is_err(), "{}", shoe);
+        }
+    }
+}
@@ -0,0 +1,1164 @@
+use cardy::{card::Card, face::Face, hand::Hand, holder::Holder};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::error::GameError;
+use crate::event::{GameEvent, Seat};
+use crate::money::Money;
+use crate::rules::{HoleCard, Payout, RuleSet, Surrender};
+use crate::shoe::Shoe;
+use crate::strategy::{DealerRules, PlayerStrategy, View};
+use crate::{
+    face_val, get_outcome, hand_val, is_blackjack, is_pair, Decision, Outcome, BUST_THRESHOLD,
+};
+
+/// The rules being played, the shoe being dealt from and the dealer's seat, which rounds are dealt at.
+/// Every shuffle comes from a generator seeded once per table, so a seed and the same decisions replay a game exactly.
+pub struct Table {
+    rules: RuleSet,
+    seed: u64,
+    shoe: Shoe,
+    dealer: Box<dyn PlayerStrategy>,
+    events: Vec<GameEvent>,
+}
+
+impl Table {
+    /// A table whose dealer draws by the rule set.
+    pub fn new(rules: RuleSet, seed: u64) -> Table {
+        Table::with_dealer(rules, seed, Box::new(DealerRules::new(rules)))
+    }
+
+    /// A table with something other than the rule set deciding the dealer's moves.
+    pub fn with_dealer(rules: RuleSet, seed: u64, dealer: Box<dyn PlayerStrategy>) -> Table {
+        // a new shoe is shuffled, and burned from if the rules say so, before the first round
+        let events = if rules.burns_card {
+            vec![GameEvent::CardBurned]
+        } else {
+            Vec::new()
+        };
+        Table {
+            rules,
+            seed,
+            shoe: Shoe::new(&rules, StdRng::seed_from_u64(seed)),
+            dealer,
+            events,
+        }
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The seed the table's shuffles come from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn shoe(&self) -> &Shoe {
+        &self.shoe
+    }
+
+    /// Deals a new round with `bet` on the player's hand.
+    /// The shoe is reshuffled once the round finishes if the cut card comes out while it's dealt.
+    pub fn deal(&mut self, bet: Money) -> Result<Round<'_>, GameError> {
+        Round::deal(self, bet)
+    }
+
+    /// Takes every event since they were last drained, oldest first.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Deals the next card from the shoe. If the shoe runs dry partway through a round, the discard tray is
+    /// shuffled in without the cards on the table, and the shoe is shuffled in full once the round is over.
+    fn deal_card(&mut self) -> Result<Card, GameError> {
+        if let Some(card) = self.shoe.deal() {
+            return Ok(card);
+        }
+        if !self.shoe.shuffle_in_discards() {
+            return Err(GameError::OutOfCards);
+        }
+        self.events.push(GameEvent::DiscardsShuffledIn);
+        self.shoe.deal().ok_or(GameError::OutOfCards)
+    }
+}
+
+/// How one of the player's hands was settled.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HandResult {
+    /// Won with a natural (or a split ace and ten, if the rules count it), paid at the blackjack payout.
+    Blackjack,
+    /// Took even money on a blackjack against the dealer's ace.
+    EvenMoney,
+    Won,
+    Push,
+    Lost,
+    Busted,
+    Surrendered,
+}
+
+/// One of the player's hands; there is more than one after splitting a pair.
+pub struct PlayerHand {
+    hand: Hand,
+    is_doubled: bool,
+    is_split: bool,
+    is_surrendered: bool,
+    settlement: Option<(HandResult, Money)>,
+}
+
+impl PlayerHand {
+    fn new(hand: Hand, is_split: bool) -> PlayerHand {
+        PlayerHand {
+            hand,
+            is_doubled: false,
+            is_split,
+            is_surrendered: false,
+            settlement: None,
+        }
+    }
+
+    pub fn hand(&self) -> &Hand {
+        &self.hand
+    }
+
+    pub fn is_doubled(&self) -> bool {
+        self.is_doubled
+    }
+
+    pub fn is_split(&self) -> bool {
+        self.is_split
+    }
+
+    pub fn is_surrendered(&self) -> bool {
+        self.is_surrendered
+    }
+
+    /// How the hand was settled and the change in winnings from it, once the round is over.
+    pub fn settlement(&self) -> Option<(HandResult, Money)> {
+        self.settlement
+    }
+
+    /// The amount at stake on this hand, given the bet placed at the start of the round.
+    pub fn wager(&self, bet: Money, rules: &RuleSet) -> Money {
+        if self.is_doubled {
+            bet.checked_mul(rules.double_down_multiplier as i64)
+                .expect("money overflowed")
+        } else {
+            bet
+        }
+    }
+
+    pub fn is_split_aces(&self) -> bool {
+        self.is_split
+            && self
+                .hand
+                .cards()
+                .first()
+                .is_some_and(|card| card.face == Face::Ace)
+    }
+
+    /// The change in winnings when this hand wins, paid at the rule set's payout for how it was played.
+    fn winning_change(&self, bet: Money, counts_as_blackjack: bool, rules: &RuleSet) -> Money {
+        if counts_as_blackjack {
+            rules.blackjack_payout.apply(bet)
+        } else if self.is_doubled {
+            rules.double_down_payout.apply(self.wager(bet, rules))
+        } else {
+            rules.win_payout.apply(bet)
+        }
+    }
+
+    /// The change in winnings when this hand doesn't win.
+    /// A surrendered hand gets half the bet back, rounded down to the cent.
+    fn losing_change(&self, bet: Money, rules: &RuleSet) -> Money {
+        if self.is_surrendered {
+            -(bet - bet.half())
+        } else {
+            -self.wager(bet, rules)
+        }
+    }
+}
+
+/// What a round is waiting on next.
+#[derive(Clone, PartialEq, Debug)]
+pub enum RoundState {
+    /// Under early surrender, the player makes their first decision before the dealer checks for a blackjack.
+    /// Anything but surrendering is carried out once the check is over.
+    EarlyDecision { legal: Vec<Decision> },
+    /// The player may take even money on their blackjack against the dealer's ace.
+    EvenMoney,
+    /// The player may insure against a dealer blackjack with up to `max`.
+    Insurance { max: Money },
+    /// The player must make one of the `legal` decisions for the hand at `hand_index`.
+    Decision {
+        hand_index: usize,
+        legal: Vec<Decision>,
+    },
+    /// The dealer is playing out their hand, one move per [`Round::advance_dealer`].
+    DealerTurn,
+    /// Every bet has been settled.
+    Finished { change: Money },
+}
+
+/// Watches a round played by [`Round::play_observed`], such as to draw it on the screen or keep the count.
+pub trait RoundObserver {
+    /// Called with each event at the table, oldest first.
+    fn observe(&mut self, event: &GameEvent);
+
+    /// Called before the round moves on from `state`, once every event leading up to it has been observed,
+    /// such as to give the player time to see what happened. Does nothing by default.
+    fn before(&mut self, _state: &RoundState) {}
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Phase {
+    EarlySurrender,
+    EvenMoney,
+    Insurance,
+    PlayerTurn,
+    DealerTurn,
+    Finished,
+}
+
+/// A single round of blackjack, played out by answering its [`RoundState`] until it's finished.
+/// Anything that deals a card fails with [`GameError::OutOfCards`] if every card is already on the table,
+/// after which the round can't be played any further.
+pub struct Round<'t> {
+    table: &'t mut Table,
+    rules: RuleSet,
+    bet: Money,
+    dealer_hand: Hand,
+    is_hole_card_hidden: bool,
+    has_dealer_peeked: bool,
+    hands: Vec<PlayerHand>,
+    active_hand: usize,
+    phase: Phase,
+    pending_decision: Option<Decision>,
+    insurance: Money,
+    insurance_change: Option<Money>,
+}
+
+impl<'t> Round<'t> {
+    fn deal(table: &'t mut Table, bet: Money) -> Result<Round<'t>, GameError> {
+        let rules = table.rules;
+        let mut round = Round {
+            table,
+            rules,
+            bet,
+            dealer_hand: Hand::new(),
+            is_hole_card_hidden: false,
+            has_dealer_peeked: false,
+            hands: vec![PlayerHand::new(Hand::new(), false)],
+            active_hand: 0,
+            phase: Phase::EarlySurrender,
+            pending_decision: None,
+            insurance: Money::ZERO,
+            insurance_change: None,
+        };
+        round.emit(GameEvent::RoundStarted { bet });
+
+        // the hole card is always dealt face down, and isn't dealt at all without a hole card
+        round.deal_to_dealer(false)?;
+        if rules.hole_card == HoleCard::Peek {
+            round.deal_to_dealer(true)?;
+        }
+        for _ in 0..2 {
+            round.deal_to_player(0)?;
+        }
+
+        if rules.surrender != Surrender::Early || is_blackjack(&round.hands[0].hand) {
+            round.offer_insurance()?;
+        }
+        Ok(round)
+    }
+
+    pub fn state(&self) -> RoundState {
+        match self.phase {
+            Phase::EarlySurrender => RoundState::EarlyDecision {
+                legal: self.legal_decisions(),
+            },
+            Phase::EvenMoney => RoundState::EvenMoney,
+            Phase::Insurance => RoundState::Insurance {
+                max: self.max_insurance(),
+            },
+            Phase::PlayerTurn => RoundState::Decision {
+                hand_index: self.active_hand,
+                legal: self.legal_decisions(),
+            },
+            Phase::DealerTurn => RoundState::DealerTurn,
+            Phase::Finished => RoundState::Finished {
+                change: self.change(),
+            },
+        }
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    pub fn bet(&self) -> Money {
+        self.bet
+    }
+
+    pub fn dealer_hand(&self) -> &Hand {
+        &self.dealer_hand
+    }
+
+    pub fn dealer_upcard(&self) -> Face {
+        self.dealer_hand.cards()[0].face
+    }
+
+    /// Whether the dealer's second card is still face down.
+    pub fn is_hole_card_hidden(&self) -> bool {
+        self.is_hole_card_hidden
+    }
+
+    /// Whether the dealer has looked at their hole card for a blackjack.
+    pub fn has_dealer_peeked(&self) -> bool {
+        self.has_dealer_peeked
+    }
+
+    /// Whether the dealer's second card is known and makes a blackjack.
+    pub fn dealer_has_blackjack(&self) -> bool {
+        self.is_dealer_hand_complete() && is_blackjack(&self.dealer_hand)
+    }
+
+    pub fn hands(&self) -> &[PlayerHand] {
+        &self.hands
+    }
+
+    /// The change in winnings from an insurance bet, once it has been settled.
+    pub fn insurance_change(&self) -> Option<Money> {
+        self.insurance_change
+    }
+
+    /// The total change in winnings from the round so far.
+    pub fn change(&self) -> Money {
+        let hands_change: Money = self
+            .hands
+            .iter()
+            .filter_map(|player_hand| player_hand.settlement)
+            .map(|(_, change)| change)
+            .sum();
+        hands_change + self.insurance_change.unwrap_or(Money::ZERO)
+    }
+
+    /// Half of the bet, rounded down to the cent.
+    pub fn max_insurance(&self) -> Money {
+        self.bet.half()
+    }
+
+    /// Takes every event at the table since they were last drained, oldest first.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.table.drain_events()
+    }
+
+    /// What the player can see of the hand they're making a choice for.
+    pub fn view(&self) -> View<'_> {
+        let legal = match self.phase {
+            Phase::EarlySurrender | Phase::PlayerTurn => self.legal_decisions(),
+            _ => Vec::new(),
+        };
+        View {
+            hand: &self.hands[self.active_hand].hand,
+            dealer_upcard: self.dealer_upcard(),
+            bet: self.bet,
+            legal,
+            is_before_peek: self.phase == Phase::EarlySurrender
+                && self.rules.hole_card == HoleCard::Peek,
+            opposing_total: None,
+        }
+    }
+
+    /// Plays the rest of the round with `player` making every choice. Returns the change in winnings.
+    /// The round's events are left at the table to be drained.
+    pub fn play(&mut self, player: &mut dyn PlayerStrategy) -> Result<Money, GameError> {
+        loop {
+            if let Some(change) = self.step(player)? {
+                return Ok(change);
+            }
+        }
+    }
+
+    /// Plays the rest of the round like [`Round::play`], draining every event into `observer` as it happens.
+    pub fn play_observed(
+        &mut self,
+        player: &mut dyn PlayerStrategy,
+        observer: &mut dyn RoundObserver,
+    ) -> Result<Money, GameError> {
+        loop {
+            for event in self.drain_events() {
+                observer.observe(&event);
+            }
+            observer.before(&self.state());
+            if let Some(change) = self.step(player)? {
+                return Ok(change);
+            }
+        }
+    }
+
+    /// Answers the current state with `player`, or returns the change in winnings once the round is finished.
+    fn step(&mut self, player: &mut dyn PlayerStrategy) -> Result<Option<Money>, GameError> {
+        match self.state() {
+            RoundState::EarlyDecision { .. } | RoundState::Decision { .. } => {
+                let decision = player.decide(&self.view());
+                self.decide(decision)?;
+            }
+            RoundState::EvenMoney => {
+                let is_taking_even_money = player.even_money(&self.view());
+                self.take_even_money(is_taking_even_money)?;
+            }
+            RoundState::Insurance { max } => {
+                let insurance = player.insurance(&self.view(), max);
+                self.insure(insurance)?;
+            }
+            RoundState::DealerTurn => self.advance_dealer()?,
+            RoundState::Finished { change } => return Ok(Some(change)),
+        }
+        Ok(None)
+    }
+
+    /// Makes the current decision for the player.
+    ///
+    /// # Panics
+    /// If the player isn't being asked for a decision, or `decision` isn't one of the legal ones.
+    pub fn decide(&mut self, decision: Decision) -> Result<(), GameError> {
+        assert!(
+            matches!(self.phase, Phase::EarlySurrender | Phase::PlayerTurn),
+            "the player isn't being asked for a decision"
+        );
+        assert!(
+            self.legal_decisions().contains(&decision),
+            "{} isn't a legal decision right now",
+            decision.name()
+        );
+
+        if self.phase == Phase::EarlySurrender {
+            if decision == Decision::Surrender {
+                self.emit(GameEvent::Surrendered { hand: 0 });
+                self.surrender_hand(0);
+                self.finish();
+            } else {
+                self.pending_decision = Some(decision);
+                self.offer_insurance()?;
+            }
+            return Ok(());
+        }
+
+        let index = self.active_hand;
+        match decision {
+            Decision::Hit => {
+                self.deal_to_player(index)?;
+                if hand_val(&self.hands[index].hand).total >= BUST_THRESHOLD {
+                    self.stop_hand(index);
+                    self.next_hand()?;
+                }
+            }
+            Decision::Stand => {
+                self.stop_hand(index);
+                self.next_hand()?;
+            }
+            Decision::Double => {
+                // they must hit once and stand immediately after
+                self.hands[index].is_doubled = true;
+                self.emit(GameEvent::Doubled { hand: index });
+                self.deal_to_player(index)?;
+                self.stop_hand(index);
+                self.next_hand()?;
+            }
+            Decision::Split => {
+                let player_hand = &mut self.hands[index];
+                let card = player_hand
+                    .hand
+                    .cards
+                    .pop()
+                    .expect("player unexpectedly has no cards to split");
+                player_hand.is_split = true;
+
+                let mut split_hand = Hand::new();
+                split_hand.push_card(card);
+                self.hands
+                    .insert(index + 1, PlayerHand::new(split_hand, true));
+                self.emit(GameEvent::Split {
+                    hand: index,
+                    num_hands: self.hands.len(),
+                });
+                self.start_hand(index)?;
+            }
+            Decision::Surrender => {
+                self.hands[index].is_surrendered = true;
+                self.emit(GameEvent::Surrendered { hand: index });
+                self.next_hand()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers the offer of even money.
+    ///
+    /// # Panics
+    /// If even money isn't being offered.
+    pub fn take_even_money(&mut self, is_taking_even_money: bool) -> Result<(), GameError> {
+        assert_eq!(
+            self.phase,
+            Phase::EvenMoney,
+            "even money isn't being offered"
+        );
+
+        if is_taking_even_money {
+            self.emit(GameEvent::EvenMoneyTaken);
+            let change = Payout::EVEN.apply(self.bet);
+            self.hands[0].settlement = Some((HandResult::EvenMoney, change));
+            self.finish();
+        } else {
+            self.check_blackjacks()?;
+        }
+        Ok(())
+    }
+
+    /// Places an insurance bet of `insurance`, where 0 declines it.
+    ///
+    /// # Panics
+    /// If insurance isn't being offered, or `insurance` is more than [`Round::max_insurance`].
+    pub fn insure(&mut self, insurance: Money) -> Result<(), GameError> {
+        assert_eq!(
+            self.phase,
+            Phase::Insurance,
+            "insurance isn't being offered"
+        );
+        assert!(
+            (Money::ZERO..=self.max_insurance()).contains(&insurance),
+            "insurance can be at most half of the bet"
+        );
+
+        self.insurance = insurance;
+        if insurance.is_positive() {
+            self.emit(GameEvent::Insured { amount: insurance });
+        }
+        self.check_blackjacks()
+    }
+
+    /// Plays the dealer's next move: turning over (or dealing) their second card, then hitting until they stand.
+    ///
+    /// # Panics
+    /// If it isn't the dealer's turn.
+    pub fn advance_dealer(&mut self) -> Result<(), GameError> {
+        assert_eq!(self.phase, Phase::DealerTurn, "it isn't the dealer's turn");
+
+        if !self.is_dealer_hand_complete() {
+            self.complete_dealer_hand()?;
+            if self.rules.hole_card == HoleCard::NoHoleCard {
+                let dealer_has_blackjack = is_blackjack(&self.dealer_hand);
+                self.settle_insurance(dealer_has_blackjack);
+                if dealer_has_blackjack {
+                    self.settle_against_late_blackjack();
+                }
+            }
+            return Ok(());
+        }
+
+        let view = View {
+            hand: &self.dealer_hand,
+            dealer_upcard: self.dealer_hand.cards()[0].face,
+            bet: self.bet,
+            legal: vec![Decision::Hit, Decision::Stand],
+            is_before_peek: false,
+            opposing_total: self.score_to_beat(),
+        };
+        match self.table.dealer.decide(&view) {
+            Decision::Hit => {
+                self.deal_to_dealer(false)?;
+                let value = hand_val(&self.dealer_hand);
+                if value.is_bust() {
+                    self.emit(GameEvent::Busted {
+                        seat: Seat::Dealer,
+                        total: value.total,
+                    });
+                    self.settle();
+                }
+            }
+            _ => {
+                self.emit(GameEvent::Stood {
+                    seat: Seat::Dealer,
+                    value: hand_val(&self.dealer_hand),
+                });
+                self.settle();
+            }
+        }
+        Ok(())
+    }
+
+    /// The decisions available for the hand being played.
+    fn legal_decisions(&self) -> Vec<Decision> {
+        let player_hand = &self.hands[self.active_hand];
+        // anything but hitting and standing can only be done on the first two cards
+        if player_hand.hand.cards().len() != 2 {
+            return vec![Decision::Hit, Decision::Stand];
+        }
+
+        let mut legal = vec![Decision::Hit, Decision::Stand, Decision::Double];
+        if self.hands.len() < self.rules.max_split_hands && is_pair(&player_hand.hand) {
+            legal.push(Decision::Split);
+        }
+        // surrendering is only allowed on the hand as it was originally dealt
+        if self.rules.surrender != Surrender::NotAllowed && !player_hand.is_split {
+            legal.push(Decision::Surrender);
+        }
+        legal
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.table.events.push(event);
+    }
+
+    fn deal_to_dealer(&mut self, is_face_down: bool) -> Result<(), GameError> {
+        let card = self.table.deal_card()?;
+        let card = if is_face_down {
+            self.is_hole_card_hidden = true;
+            card.hidden()
+        } else {
+            card
+        };
+        self.dealer_hand.push_card(card);
+        self.emit(GameEvent::CardDealt {
+            seat: Seat::Dealer,
+            card,
+            is_face_down,
+        });
+        Ok(())
+    }
+
+    fn deal_to_player(&mut self, index: usize) -> Result<(), GameError> {
+        let card = self.table.deal_card()?;
+        self.hands[index].hand.push_card(card);
+        self.emit(GameEvent::CardDealt {
+            seat: Seat::Player(index),
+            card,
+            is_face_down: false,
+        });
+        Ok(())
+    }
+
+    /// Records the hand at `index` as done drawing, either standing or busted.
+    fn stop_hand(&mut self, index: usize) {
+        let seat = Seat::Player(index);
+        let value = hand_val(&self.hands[index].hand);
+        self.emit(if value.is_bust() {
+            GameEvent::Busted {
+                seat,
+                total: value.total,
+            }
+        } else {
+            GameEvent::Stood { seat, value }
+        });
+    }
+
+    fn is_dealer_hand_complete(&self) -> bool {
+        self.dealer_hand.cards().len() >= 2 && !self.is_hole_card_hidden
+    }
+
+    /// Turns the dealer's hole card face up, or deals their second card when playing without a hole card.
+    fn complete_dealer_hand(&mut self) -> Result<(), GameError> {
+        if self.is_hole_card_hidden {
+            let card = self
+                .dealer_hand
+                .cards
+                .pop()
+                .expect("dealer unexpectedly has no cards after being dealt two")
+                .revealed();
+            self.dealer_hand.push_card(card);
+            self.is_hole_card_hidden = false;
+            self.emit(GameEvent::HoleCardRevealed { card });
+        } else if self.dealer_hand.cards().len() < 2 {
+            self.deal_to_dealer(false)?;
+        }
+        Ok(())
+    }
+
+    /// The best total among the player's hands still standing, which a smart dealer tries to beat.
+    fn score_to_beat(&self) -> Option<usize> {
+        self.hands
+            .iter()
+            .filter(|player_hand| !player_hand.is_surrendered)
+            .filter_map(|player_hand| match get_outcome(&player_hand.hand) {
+                Outcome::Holding(sum) => Some(sum),
+                Outcome::Bust => None,
+            })
+            .max()
+    }
+
+    fn offer_insurance(&mut self) -> Result<(), GameError> {
+        if self.dealer_upcard() == Face::Ace {
+            self.phase = if is_blackjack(&self.hands[0].hand) {
+                Phase::EvenMoney
+            } else {
+                Phase::Insurance
+            };
+            return Ok(());
+        }
+        self.check_blackjacks()
+    }
+
+    fn settle_insurance(&mut self, dealer_has_blackjack: bool) {
+        if self.insurance.is_positive() {
+            let change = if dealer_has_blackjack {
+                self.rules.insurance_payout.apply(self.insurance)
+            } else {
+                -self.insurance
+            };
+            self.insurance_change = Some(change);
+            self.emit(GameEvent::InsuranceSettled { change });
+        }
+    }
+
+    /// Has the dealer peek for a blackjack, then ends the round if either side has one.
+    fn check_blackjacks(&mut self) -> Result<(), GameError> {
+        let player_has_blackjack = is_blackjack(&self.hands[0].hand);
+
+        if self.rules.hole_card == HoleCard::NoHoleCard {
+            // without a hole card, the dealer can still tie a blackjack with their second card
+            if player_has_blackjack {
+                self.complete_dealer_hand()?;
+                let dealer_has_blackjack = is_blackjack(&self.dealer_hand);
+                self.settle_insurance(dealer_has_blackjack);
+                self.settle_blackjacks(true, dealer_has_blackjack);
+                return Ok(());
+            }
+            return self.start_player_turn();
+        }
+
+        // the dealer only peeks when showing an ace or a ten-value card
+        let upcard_val = face_val(self.dealer_upcard());
+        if upcard_val == 1 || upcard_val == 10 {
+            self.has_dealer_peeked = true;
+            let has_blackjack = is_blackjack(&self.dealer_hand);
+            self.emit(GameEvent::DealerPeeked { has_blackjack });
+            if has_blackjack {
+                self.complete_dealer_hand()?;
+            }
+        }
+        let dealer_has_blackjack = self.has_dealer_peeked && is_blackjack(&self.dealer_hand);
+        self.settle_insurance(dealer_has_blackjack);
+
+        if player_has_blackjack || dealer_has_blackjack {
+            self.settle_blackjacks(player_has_blackjack, dealer_has_blackjack);
+            return Ok(());
+        }
+        self.start_player_turn()
+    }
+
+    fn settle_blackjacks(&mut self, player_has_blackjack: bool, dealer_has_blackjack: bool) {
+        let settlement = match (player_has_blackjack, dealer_has_blackjack) {
+            (true, true) => (HandResult::Push, Money::ZERO),
+            (true, false) => (
+                HandResult::Blackjack,
+                self.rules.blackjack_payout.apply(self.bet),
+            ),
+            _ => (HandResult::Lost, -self.bet),
+        };
+        self.hands[0].settlement = Some(settlement);
+        self.finish();
+    }
+
+    fn start_player_turn(&mut self) -> Result<(), GameError> {
+        self.phase = Phase::PlayerTurn;
+        self.emit(GameEvent::TurnStarted {
+            seat: Seat::Player(0),
+        });
+        self.start_hand(0)?;
+
+        if let Some(decision) = self.pending_decision.take() {
+            if self.phase == Phase::PlayerTurn && self.legal_decisions().contains(&decision) {
+                self.decide(decision)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the hand at `index` the one being played, dealing its second card if it was split.
+    fn start_hand(&mut self, index: usize) -> Result<(), GameError> {
+        self.active_hand = index;
+
+        // hands made by splitting only hold one card until they're played
+        if self.hands[index].hand.cards().len() == 1 {
+            self.deal_to_player(index)?;
+
+            if self.rules.split_aces_one_card && self.hands[index].is_split_aces() {
+                self.stop_hand(index);
+                return self.next_hand();
+            }
+        }
+
+        if hand_val(&self.hands[index].hand).total == BUST_THRESHOLD {
+            self.stop_hand(index);
+            self.next_hand()?;
+        }
+        Ok(())
+    }
+
+    fn next_hand(&mut self) -> Result<(), GameError> {
+        if self.active_hand + 1 < self.hands.len() {
+            let index = self.active_hand + 1;
+            self.emit(GameEvent::TurnStarted {
+                seat: Seat::Player(index),
+            });
+            self.start_hand(index)
+        } else {
+            self.end_player_turn()
+        }
+    }
+
+    fn end_player_turn(&mut self) -> Result<(), GameError> {
+        let is_every_hand_lost = self.hands.iter().all(|player_hand| {
+            player_hand.is_surrendered || get_outcome(&player_hand.hand) == Outcome::Bust
+        });
+        if !is_every_hand_lost {
+            self.phase = Phase::DealerTurn;
+            self.emit(GameEvent::TurnStarted { seat: Seat::Dealer });
+            return Ok(());
+        }
+
+        // the dealer doesn't need to play, unless there's insurance riding on their second card
+        if self.rules.hole_card == HoleCard::NoHoleCard && self.insurance.is_positive() {
+            self.complete_dealer_hand()?;
+            let dealer_has_blackjack = is_blackjack(&self.dealer_hand);
+            self.settle_insurance(dealer_has_blackjack);
+        }
+        for index in 0..self.hands.len() {
+            if self.hands[index].is_surrendered {
+                self.surrender_hand(index);
+            } else {
+                let change = self.hands[index].losing_change(self.bet, &self.rules);
+                self.hands[index].settlement = Some((HandResult::Busted, change));
+            }
+        }
+        self.finish();
+        Ok(())
+    }
+
+    fn surrender_hand(&mut self, index: usize) {
+        let player_hand = &mut self.hands[index];
+        player_hand.is_surrendered = true;
+        player_hand.settlement = Some((
+            HandResult::Surrendered,
+            player_hand.losing_change(self.bet, &self.rules),
+        ));
+    }
+
+    /// Without a hole card, a dealer blackjack is only found after the player has acted,
+    /// and takes the original bet (or every bet, depending on the rules). Busted hands were lost before it was found.
+    fn settle_against_late_blackjack(&mut self) {
+        for index in 0..self.hands.len() {
+            let player_hand = &mut self.hands[index];
+            player_hand.settlement = Some(if player_hand.is_surrendered {
+                (
+                    HandResult::Surrendered,
+                    player_hand.losing_change(self.bet, &self.rules),
+                )
+            } else if get_outcome(&player_hand.hand) == Outcome::Bust {
+                (
+                    HandResult::Busted,
+                    player_hand.losing_change(self.bet, &self.rules),
+                )
+            } else if !self.rules.original_bets_only {
+                (
+                    HandResult::Lost,
+                    player_hand.losing_change(self.bet, &self.rules),
+                )
+            } else if index == 0 {
+                (HandResult::Lost, -self.bet)
+            } else {
+                (HandResult::Push, Money::ZERO) // bets from splitting are returned
+            });
+        }
+        self.finish();
+    }
+
+    /// Compares each of the player's hands against the dealer's finished hand.
+    fn settle(&mut self) {
+        let dealer_outcome = get_outcome(&self.dealer_hand);
+
+        for player_hand in &mut self.hands {
+            if player_hand.is_surrendered {
+                let change = player_hand.losing_change(self.bet, &self.rules);
+                player_hand.settlement = Some((HandResult::Surrendered, change));
+                continue;
+            }
+
+            let player_outcome = get_outcome(&player_hand.hand);
+            let counts_as_blackjack = self.rules.split_blackjack_counts
+                && player_hand.is_split
+                && is_blackjack(&player_hand.hand);
+
+            player_hand.settlement = Some(match player_outcome {
+                Outcome::Bust => (
+                    HandResult::Busted,
+                    player_hand.losing_change(self.bet, &self.rules),
+                ),
+                // a blackjack beats a dealer's 21 made from three or more cards
+                _ if counts_as_blackjack && player_outcome >= dealer_outcome => (
+                    HandResult::Blackjack,
+                    player_hand.winning_change(self.bet, true, &self.rules),
+                ),
+                _ if player_outcome > dealer_outcome => (
+                    HandResult::Won,
+                    player_hand.winning_change(self.bet, false, &self.rules),
+                ),
+                _ if player_outcome == dealer_outcome => (HandResult::Push, Money::ZERO),
+                _ => (
+                    HandResult::Lost,
+                    player_hand.losing_change(self.bet, &self.rules),
+                ),
+            });
+        }
+        self.finish();
+    }
+
+    /// Ends the round, reporting how each hand was settled, and clears the table into the discard tray.
+    fn finish(&mut self) {
+        self.phase = Phase::Finished;
+        for index in 0..self.hands.len() {
+            if let Some((result, change)) = self.hands[index].settlement {
+                self.emit(GameEvent::HandSettled {
+                    hand: index,
+                    result,
+                    change,
+                });
+            }
+        }
+        let change = self.change();
+        self.emit(GameEvent::RoundOver { change });
+
+        let cards = self
+            .dealer_hand
+            .cards()
+            .iter()
+            .chain(self.hands.iter().flat_map(|hand| hand.hand.cards()))
+            .copied()
+            .collect::<Vec<Card>>();
+        self.table.shoe.discard(cards);
+        if self.table.shoe.is_cut_card_out() {
+            let is_card_burned = self.table.shoe.shuffle();
+            self.emit(GameEvent::Reshuffled);
+            if is_card_burned {
+                self.emit(GameEvent::CardBurned);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_card;
+
+    const BET: Money = Money::from_units(10);
+
+    fn hand_of(faces: &[Face]) -> Hand {
+        let mut hand = Hand::new();
+        for &face in faces {
+            hand.push_card(test_card(face));
+        }
+        hand
+    }
+
+    /// One of two hands split from a pair of eights, doubled if `is_doubled`.
+    fn split_hand(faces: &[Face], is_doubled: bool) -> PlayerHand {
+        let mut player_hand = PlayerHand::new(hand_of(faces), true);
+        player_hand.is_doubled = is_doubled;
+        player_hand
+    }
+
+    /// A round at `phase`, with the dealer holding `dealer_faces` (the second of them face down if
+    /// `is_hole_card_hidden`) and the player holding `hands`.
+    fn round_with<'t>(
+        table: &'t mut Table,
+        dealer_faces: &[Face],
+        is_hole_card_hidden: bool,
+        hands: Vec<PlayerHand>,
+        phase: Phase,
+    ) -> Round<'t> {
+        let mut dealer_hand = hand_of(dealer_faces);
+        if is_hole_card_hidden {
+            let card = dealer_hand.cards.pop().unwrap();
+            dealer_hand.push_card(card.hidden());
+        }
+        Round {
+            rules: *table.rules(),
+            table,
+            bet: BET,
+            dealer_hand,
+            is_hole_card_hidden,
+            has_dealer_peeked: false,
+            hands,
+            active_hand: 0,
+            phase,
+            pending_decision: None,
+            insurance: Money::ZERO,
+            insurance_change: None,
+        }
+    }
+
+    fn settlements(round: &Round) -> Vec<Option<(HandResult, Money)>> {
+        round.hands().iter().map(PlayerHand::settlement).collect()
+    }
+
+    #[test]
+    fn split_and_doubled_hands_are_settled_separately() {
+        let mut table = Table::new(RuleSet::default(), 0);
+        let hands = vec![
+            split_hand(&[Face::Eight, Face::Three, Face::Ten], true),
+            split_hand(&[Face::Eight, Face::Ten], false),
+        ];
+        let mut round = round_with(
+            &mut table,
+            &[Face::Ten, Face::Seven],
+            false,
+            hands,
+            Phase::DealerTurn,
+        );
+        round.settle();
+
+        assert_eq!(
+            settlements(&round),
+            [
+                Some((HandResult::Won, Money::from_units(20))),
+                Some((HandResult::Won, BET))
+            ]
+        );
+        assert_eq!(round.change(), Money::from_units(30));
+    }
+
+    #[test]
+    fn insurance_pays_against_a_blackjack() {
+        let mut table = Table::new(RuleSet::default(), 0);
+        let hands = vec![PlayerHand::new(hand_of(&[Face::Ten, Face::Nine]), false)];
+        let mut round = round_with(
+            &mut table,
+            &[Face::Ace, Face::King],
+            true,
+            hands,
+            Phase::Insurance,
+        );
+        round.insure(Money::from_units(5)).unwrap();
+
+        assert_eq!(round.insurance_change(), Some(BET));
+        assert_eq!(settlements(&round), [Some((HandResult::Lost, -BET))]);
+        assert_eq!(round.change(), Money::ZERO);
+    }
+
+    #[test]
+    fn insurance_is_lost_without_a_blackjack() {
+        let mut table = Table::new(RuleSet::default(), 0);
+        let hands = vec![PlayerHand::new(hand_of(&[Face::Ten, Face::Nine]), false)];
+        let mut round = round_with(
+            &mut table,
+            &[Face::Ace, Face::Seven],
+            true,
+            hands,
+            Phase::Insurance,
+        );
+        round.insure(Money::from_units(5)).unwrap();
+
+        assert_eq!(round.insurance_change(), Some(-Money::from_units(5)));
+        assert!(matches!(
+            round.state(),
+            RoundState::Decision { hand_index: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn late_blackjack_takes_only_the_original_bet() {
+        let mut table = Table::new(RuleSet::european(), 0);
+        let hands = vec![
+            split_hand(&[Face::Eight, Face::Three, Face::Ten], true),
+            split_hand(&[Face::Eight, Face::Ten], false),
+        ];
+        let mut round = round_with(
+            &mut table,
+            &[Face::Ten, Face::Ace],
+            false,
+            hands,
+            Phase::DealerTurn,
+        );
+        round.settle_against_late_blackjack();
+
+        assert_eq!(
+            settlements(&round),
+            [
+                Some((HandResult::Lost, -BET)),
+                Some((HandResult::Push, Money::ZERO))
+            ]
+        );
+        assert_eq!(round.change(), -BET);
+    }
+
+    #[test]
+    fn late_blackjack_can_take_every_bet() {
+        let rules = RuleSet {
+            original_bets_only: false,
+            ..RuleSet::european()
+        };
+        let mut table = Table::new(rules, 0);
+        let hands = vec![
+            split_hand(&[Face::Eight, Face::Three, Face::Ten], true),
+            split_hand(&[Face::Eight, Face::Ten], false),
+        ];
+        let mut round = round_with(
+            &mut table,
+            &[Face::Ten, Face::Ace],
+            false,
+            hands,
+            Phase::DealerTurn,
+        );
+        round.settle_against_late_blackjack();
+
+        assert_eq!(
+            settlements(&round),
+            [
+                Some((HandResult::Lost, -Money::from_units(20))),
+                Some((HandResult::Lost, -BET))
+            ]
+        );
+        assert_eq!(round.change(), -Money::from_units(30));
+    }
+
+    #[test]
+    fn busted_split_hands_are_lost_to_a_late_blackjack() {
+        let mut table = Table::new(RuleSet::european(), 0);
+        let hands = vec![
+            split_hand(&[Face::Eight, Face::Six, Face::Ten], false),
+            split_hand(&[Face::Eight, Face::Ten], false),
+        ];
+        let mut round = round_with(
+            &mut table,
+            &[Face::Ten, Face::Ace],
+            false,
+            hands,
+            Phase::DealerTurn,
+        );
+        round.settle_against_late_blackjack();
+
+        assert_eq!(
+            settlements(&round),
+            [
+                Some((HandResult::Busted, -BET)),
+                Some((HandResult::Push, Money::ZERO))
+            ]
+        );
+        assert_eq!(round.change(), -BET);
+    }
+
+    #[test]
+    fn a_new_shoe_burns_a_card_if_the_rules_say_so() {
+        let rules = RuleSet {
+            burns_card: true,
+            ..RuleSet::default()
+        };
+        let mut table = Table::new(rules, 0);
+        assert_eq!(
+            table.drain_events().collect::<Vec<_>>(),
+            [GameEvent::CardBurned]
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use std::fmt::{Display, Formatter};
+
+/// Something that stops a round from being played out.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum GameError {
+    /// Every card is already on the table, so there's nothing left to deal even after shuffling in the discard tray.
+    OutOfCards,
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            GameError::OutOfCards => write!(f, "every card in the shoe is already on the table"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use cardy::face::Face;
+
+use crate::chart::{ratio, ChartCell};
+use crate::dealer::{DealerHands, DealerOdds, DEALER_BUST};
+use crate::rules::{HoleCard, RuleSet, Surrender, DEALER_STAND_TOTAL};
+use crate::shoe::ShoeComposition;
+use crate::{face_val, HandValue, BUST_THRESHOLD};
+
+/// Works out the exact expected value of every decision on a hand, with each card drawn from the shoe as it
+/// really stands rather than from an infinite one.
+///
+/// Everything worked out from a given composition is cached and kept between queries, so questions about the same
+/// shoe get quicker as they go. The dealer is assumed to play by
+/// [`DealerStyle::Casino`](crate::rules::DealerStyle::Casino).
+pub struct EvCalculator {
+    rules: RuleSet,
+    /// Whether a dealer blackjack is still possible as the player plays, because there's no hole card to peek at.
+    /// Otherwise values are first added up over the hole cards that aren't a blackjack, then conditioned on that.
+    is_blackjack_unknown: bool,
+    dealer: DealerHands,
+    /// How the dealer finishes, by upcard and the composition left once the upcard is out.
+    dealer_odds: HashMap<(usize, ShoeComposition), DealerOdds>,
+    /// Best value of hitting or standing, by upcard, hard total, whether the hand holds an ace and whether it's split.
+    hit_or_stand: HashMap<(usize, usize, bool, bool, ShoeComposition), f64>,
+}
+
+impl EvCalculator {
+    pub fn new(rules: &RuleSet) -> EvCalculator {
+        EvCalculator {
+            rules: *rules,
+            is_blackjack_unknown: rules.hole_card == HoleCard::NoHoleCard,
+            dealer: DealerHands::new(rules),
+            dealer_odds: HashMap::new(),
+            hit_or_stand: HashMap::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// The expected change in winnings per unit bet of each decision on `player_cards` against `upcard`, with every
+    /// card to come drawn from `shoe`. The player's cards and the upcard should already be out of `shoe`.
+    ///
+    /// Split hands are each played as though the other weren't there, since valuing them together would mean going
+    /// through every way they could share out the shoe. Doubling and surrendering are valued as if the hand still has
+    /// only its first two cards.
+    ///
+    /// Returns an error if `shoe` could run out before the hand and the dealer's are played out, or if every card left
+    /// in it gives the dealer a blackjack after a peek.
+    ///
+    /// # Panics
+    /// If the hand is busted or has fewer than two cards.
+    pub fn evaluate(
+        &mut self,
+        player_cards: &[Face],
+        upcard: Face,
+        shoe: &ShoeComposition,
+    ) -> Result<ChartCell, String> {
+        let value = HandValue::from_faces(player_cards.iter().copied());
+        assert!(
+            player_cards.len() >= 2 && !value.is_bust(),
+            "only unbusted hands of two or more cards are evaluated"
+        );
+        let upcard = face_val(upcard);
+        let hard_total = player_cards.iter().map(|&face| face_val(face)).sum();
+        let has_ace = player_cards.contains(&Face::Ace);
+        let pair_val = match *player_cards {
+            [first, second]
+                if face_val(first) == face_val(second) && self.rules.max_split_hands >= 2 =>
+            {
+                Some(face_val(first))
+            }
+            _ => None,
+        };
+        if !self.can_play_out(hard_total, pair_val, upcard, *shoe) {
+            return Err("the shoe could run out before the hand is played out".to_string());
+        }
+
+        // after a peek, values are only added up over hole cards that aren't a blackjack, so they're divided by the
+        // chance of that to condition on it
+        let odds = self.dealer_odds(upcard, *shoe);
+        let no_blackjack = if self.is_blackjack_unknown {
+            1.
+        } else {
+            1. - odds.blackjack
+        };
+        if no_blackjack <= 0. {
+            return Err("every card left in the shoe gives the dealer a blackjack".to_string());
+        }
+
+        let win = ratio(self.rules.win_payout);
+        let blackjack_loss = self.blackjack_loss(1., 1.);
+        let stand = self.stand(upcard, value, *shoe, win, 1., blackjack_loss);
+        let hit = self.hit(upcard, hard_total, has_ace, false, *shoe);
+        let double = self.double(upcard, hard_total, has_ace, 1., *shoe);
+        let split = pair_val.map(|val| 2. * self.split_hand(upcard, val, 2, *shoe));
+
+        // an early surrender is decided before the peek, so anything else still risks the original bet to a blackjack
+        let is_before_peek = !self.is_blackjack_unknown
+            && self.rules.surrender == Surrender::Early
+            && player_cards.len() == 2;
+        let dealer_blackjack = if is_before_peek { odds.blackjack } else { 0. };
+        let with_blackjack =
+            |ev: f64| (1. - dealer_blackjack) * ev / no_blackjack - dealer_blackjack;
+        Ok(ChartCell {
+            hit: with_blackjack(hit),
+            stand: with_blackjack(stand),
+            double: with_blackjack(double),
+            split: split.map(with_blackjack),
+            surrender: (self.rules.surrender != Surrender::NotAllowed).then_some(-0.5),
+        })
+    }
+
+    /// Whether `shoe` holds enough cards for the player to hit until they bust, on the hand or one split from a pair of
+    /// `pair_val`, and for the dealer to then draw to a hard 17. The lowest cards make for the longest hands, so they're
+    /// the ones drawn.
+    fn can_play_out(
+        &self,
+        hard_total: usize,
+        pair_val: Option<usize>,
+        upcard: usize,
+        mut shoe: ShoeComposition,
+    ) -> bool {
+        let hard_total = match pair_val {
+            Some(val) => {
+                for _ in 2..self.rules.max_split_hands {
+                    shoe = shoe.without_val(val).unwrap_or(shoe);
+                }
+                val
+            }
+            None => hard_total,
+        };
+        shoe.draw_lowest(hard_total, BUST_THRESHOLD + 1)
+            && shoe.draw_lowest(upcard, DEALER_STAND_TOTAL)
+    }
+
+    /// What a hand that would lose `loss` gives up to a dealer blackjack without a hole card, where `share` is its
+    /// part of the original bet.
+    fn blackjack_loss(&self, loss: f64, share: f64) -> f64 {
+        if self.rules.original_bets_only {
+            share
+        } else {
+            loss
+        }
+    }
+
+    /// Value of standing on `value`, winning `win` or losing `loss`, or losing `blackjack_loss` to a dealer blackjack.
+    fn stand(
+        &mut self,
+        upcard: usize,
+        value: HandValue,
+        shoe: ShoeComposition,
+        win: f64,
+        loss: f64,
+        blackjack_loss: f64,
+    ) -> f64 {
+        let odds = self.dealer_odds(upcard, shoe);
+        let blackjack = if self.is_blackjack_unknown {
+            odds.blackjack
+        } else {
+            0.
+        };
+        if value.is_bust() {
+            return -loss * (1. - odds.blackjack + blackjack);
+        }
+
+        let mut ev = odds.finishes[DEALER_BUST] * win - blackjack * blackjack_loss;
+        for (index, chance) in odds.finishes[..DEALER_BUST].iter().enumerate() {
+            let dealer_total = DEALER_STAND_TOTAL + index;
+            if dealer_total < value.total {
+                ev += chance * win;
+            } else if dealer_total > value.total {
+                ev -= chance * loss;
+            }
+        }
+        ev
+    }
+
+    /// Value of a split 21 paid as a blackjack, which only pushes against a dealer blackjack.
+    fn natural(&mut self, upcard: usize, shoe: ShoeComposition) -> f64 {
+        let odds = self.dealer_odds(upcard, shoe);
+        (1. - odds.blackjack) * ratio(self.rules.blackjack_payout)
+    }
+
+    /// Value of taking a card and then playing on as well as possible by hitting or standing.
+    fn hit(
+        &mut self,
+        upcard: usize,
+        hard_total: usize,
+        has_ace: bool,
+        is_split: bool,
+        shoe: ShoeComposition,
+    ) -> f64 {
+        shoe.draws()
+            .map(|(val, chance, rest)| {
+                chance
+                    * self.hit_or_stand(
+                        upcard,
+                        hard_total + val,
+                        has_ace || val == 1,
+                        is_split,
+                        rest,
+                    )
+            })
+            .sum()
+    }
+
+    fn hit_or_stand(
+        &mut self,
+        upcard: usize,
+        hard_total: usize,
+        has_ace: bool,
+        is_split: bool,
+        shoe: ShoeComposition,
+    ) -> f64 {
+        let key = (upcard, hard_total, has_ace, is_split, shoe);
+        if let Some(&ev) = self.hit_or_stand.get(&key) {
+            return ev;
+        }
+        let value = HandValue::from_hard_total(hard_total, has_ace);
+        let blackjack_loss = self.blackjack_loss(1., if is_split { 0.5 } else { 1. });
+        let stand = self.stand(
+            upcard,
+            value,
+            shoe,
+            ratio(self.rules.win_payout),
+            1.,
+            blackjack_loss,
+        );
+        let ev = if value.is_bust() {
+            stand
+        } else {
+            stand.max(self.hit(upcard, hard_total, has_ace, is_split, shoe))
+        };
+        self.hit_or_stand.insert(key, ev);
+        ev
+    }
+
+    /// Value of doubling the wager and taking exactly one more card, on a hand that's `share` of the original bet.
+    fn double(
+        &mut self,
+        upcard: usize,
+        hard_total: usize,
+        has_ace: bool,
+        share: f64,
+        shoe: ShoeComposition,
+    ) -> f64 {
+        let multiplier = self.rules.double_down_multiplier as f64;
+        let win = ratio(self.rules.double_down_payout) * multiplier;
+        let blackjack_loss = self.blackjack_loss(multiplier, share);
+        shoe.draws()
+            .map(|(val, chance, rest)| {
+                let value = HandValue::from_hard_total(hard_total + val, has_ace || val == 1);
+                chance * self.stand(upcard, value, rest, win, multiplier, blackjack_loss)
+            })
+            .sum()
+    }
+
+    /// Value of one of the hands made by splitting a pair of `val`, with `num_hands` hands held after the split.
+    fn split_hand(
+        &mut self,
+        upcard: usize,
+        val: usize,
+        num_hands: usize,
+        shoe: ShoeComposition,
+    ) -> f64 {
+        let win = ratio(self.rules.win_payout);
+        let blackjack_loss = self.blackjack_loss(1., 0.5);
+        shoe.draws()
+            .map(|(drawn, chance, rest)| {
+                let (hard_total, has_ace) = (val + drawn, val == 1 || drawn == 1);
+                let value = HandValue::from_hard_total(hard_total, has_ace);
+                let played = if value.total == BUST_THRESHOLD && self.rules.split_blackjack_counts {
+                    self.natural(upcard, rest)
+                } else if value.total == BUST_THRESHOLD
+                    || (val == 1 && self.rules.split_aces_one_card)
+                {
+                    self.stand(upcard, value, rest, win, 1., blackjack_loss)
+                } else {
+                    self.hit_or_stand(upcard, hard_total, has_ace, true, rest)
+                        .max(self.double(upcard, hard_total, has_ace, 0.5, rest))
+                };
+
+                let can_resplit = drawn == val
+                    && num_hands < self.rules.max_split_hands
+                    && !(val == 1 && self.rules.split_aces_one_card);
+                let ev = if can_resplit {
+                    played.max(2. * self.split_hand(upcard, val, num_hands + 1, rest))
+                } else {
+                    played
+                };
+                chance * ev
+            })
+            .sum()
+    }
+
+    /// How the dealer finishes showing `upcard`, with the hole card and every card after it drawn from `shoe`.
+    fn dealer_odds(&mut self, upcard: usize, shoe: ShoeComposition) -> DealerOdds {
+        if let Some(&odds) = self.dealer_odds.get(&(upcard, shoe)) {
+            return odds;
+        }
+        let odds = self.dealer.odds(upcard, &shoe);
+        self.dealer_odds.insert((upcard, shoe), odds);
+        odds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cardy::{hand::Hand, holder::Holder};
+
+    use super::*;
+    use crate::chart::StrategyChart;
+    use crate::test_card;
+    use crate::Decision;
+
+    fn evaluate(rules: &RuleSet, player_cards: &[Face], upcard: Face) -> Result<ChartCell, String> {
+        let shoe = player_cards
+            .iter()
+            .chain([&upcard])
+            .try_fold(ShoeComposition::full(rules.num_decks), |shoe, &face| {
+                shoe.without(face)
+            })
+            .unwrap();
+        EvCalculator::new(rules).evaluate(player_cards, upcard, &shoe)
+    }
+
+    #[test]
+    fn standing_on_20_against_a_6_wins() {
+        let cell = evaluate(&RuleSet::default(), &[Face::Ten, Face::King], Face::Six).unwrap();
+        assert!(cell.ev(Decision::Stand).unwrap() > 0.);
+        assert_eq!(
+            cell.best(&[Decision::Hit, Decision::Stand]),
+            Decision::Stand
+        );
+    }
+
+    #[test]
+    fn agrees_with_the_chart_on_a_deep_shoe() {
+        let rules = RuleSet {
+            num_decks: 200,
+            ..RuleSet::vegas_strip()
+        };
+        let cell = evaluate(&rules, &[Face::Ten, Face::Six], Face::Ten).unwrap();
+        let chart = StrategyChart::new(&rules);
+        let mut hand = Hand::new();
+        for face in [Face::Ten, Face::Six] {
+            hand.push_card(test_card(face));
+        }
+        let expected = chart.cell(&hand, Face::Ten);
+        assert!((cell.ev(Decision::Stand).unwrap() - expected.stand).abs() < 1e-3);
+        assert!((cell.ev(Decision::Hit).unwrap() - expected.hit).abs() < 1e-3);
+    }
+
+    #[test]
+    fn rejects_shoes_that_cant_play_the_hand_out() {
+        let rules = RuleSet::default();
+        let mut calculator = EvCalculator::new(&rules);
+        let hand = [Face::Ten, Face::Six];
+        for shoe in [
+            "0,0,0,0,0,0,0,0,0,0",
+            "1,0,0,0,0,0,0,0,0,0",
+            "0,0,0,0,0,0,0,0,0,5",
+        ] {
+            let shoe: ShoeComposition = shoe.parse().unwrap();
+            assert!(
+                calculator.evaluate(&hand, Face::Ace, &shoe).is_err(),
+                "{}",
+                shoe
+            );
+        }
+    }
+}
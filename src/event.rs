@@ -0,0 +1,80 @@
+use cardy::card::Card;
+
+use crate::engine::HandResult;
+use crate::money::Money;
+use crate::HandValue;
+
+/// Who a card or move belongs to.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Seat {
+    Dealer,
+    /// One of the player's hands, by its position; splitting inserts the new hand right after the one split.
+    Player(usize),
+}
+
+/// Something that happened at the table, in the order it happened.
+/// Renderers, loggers and statistics all follow a game through these rather than its terminal output.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GameEvent {
+    /// The cut card came out during the round just finished, so the discards were shuffled back into the shoe.
+    Reshuffled,
+    /// The shoe ran out partway through a round, so the discard tray was shuffled in to keep dealing.
+    DiscardsShuffledIn,
+    /// The first card after a shuffle went into the discard tray unseen, so there's no face to show or count.
+    CardBurned,
+    RoundStarted {
+        bet: Money,
+    },
+    /// A face-down card still carries its face, so anything showing events to the player must keep it hidden
+    /// until [`GameEvent::HoleCardRevealed`].
+    CardDealt {
+        seat: Seat,
+        card: Card,
+        is_face_down: bool,
+    },
+    EvenMoneyTaken,
+    Insured {
+        amount: Money,
+    },
+    /// The dealer looked at their hole card for a blackjack.
+    DealerPeeked {
+        has_blackjack: bool,
+    },
+    HoleCardRevealed {
+        card: Card,
+    },
+    InsuranceSettled {
+        change: Money,
+    },
+    TurnStarted {
+        seat: Seat,
+    },
+    Doubled {
+        hand: usize,
+    },
+    /// The pair in `hand` was split, with its second card moved into a new hand after it.
+    Split {
+        hand: usize,
+        num_hands: usize,
+    },
+    Surrendered {
+        hand: usize,
+    },
+    /// A hand stopped drawing, whether by choice or because the rules allow no more cards.
+    Stood {
+        seat: Seat,
+        value: HandValue,
+    },
+    Busted {
+        seat: Seat,
+        total: usize,
+    },
+    HandSettled {
+        hand: usize,
+        result: HandResult,
+        change: Money,
+    },
+    RoundOver {
+        change: Money,
+    },
+}
@@ -2,23 +2,28 @@ use std::{cmp::Ordering, fmt::{Formatter, Display}, time::Duration};
 
 use cardy::{face::Face, hand::Hand, holder::Holder};
 use colored::Colorize;
-use prediput::select::Select;
+use prediput::{confirm, select::Select};
 
-/// Value for a player to bust at.
+pub mod accuracy;
+pub mod chart;
+pub mod counting;
+pub mod dealer;
+pub mod engine;
+pub mod error;
+pub mod ev;
+pub mod event;
+pub mod money;
+pub mod rules;
+pub mod shoe;
+pub mod sim;
+pub mod strategy;
+
+/// Value for a player to bust at. Unlike the rules in [`rules::RuleSet`], this is part of the game itself.
 pub const BUST_THRESHOLD: usize = 21;
-/// Value for the dealer to stand at.
-pub const DEALER_STAND_THRESHOLD: usize = 18;
-/// Value to multiply bet by when the player wins.
-pub const WIN_MULTIPLIER: f64 = 0.6; // 3/5 or 3:2
-/// Value to multiply bet by when doubling down.
-pub const DOUBLE_DOWN_MULTIPLIER: f64 = 2.;
 
 /// Time to "simulate" a card being dealt, so that the player can see what's happening without printing excess lines.
 pub const DEALING_SIMULATION_TIME: Duration = Duration::from_millis(800);
-/// Percent of deck that must be used in order for a new one to be used instead.
-pub const DECK_REPLACEMENT_THRESHOLD: f64 = 0.5;
 pub const WINNINGS_UNIT_STR: &str = "$";
-pub const STANDARD_NUM_DECKS: usize = 4;
 
 pub const PLAYER_COLOR: (u8, u8, u8) = (110, 157, 211);
 pub const DEALER_COLOR: (u8, u8, u8) = (113, 110, 211);
@@ -27,13 +32,38 @@ pub const WINNINGS_COLOR: (u8, u8, u8) = (206, 148, 22);
 pub const LIGHT_TEXT: (u8, u8, u8) = (200, 200, 200);
 pub const FG_TEXT_COLOR: (u8, u8, u8) = (160, 160, 160);
 pub const BG_TEXT_COLOR: (u8, u8, u8) = (120, 120, 120);
+pub const HINT_COLOR: (u8, u8, u8) = (130, 190, 130);
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Decision {
     Hit,
-    Stand
+    Stand,
+    Double,
+    Split,
+    Surrender
 }
 
+impl Decision {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Decision::Hit => "Hit",
+            Decision::Stand => "Stand",
+            Decision::Double => "Double down",
+            Decision::Split => "Split",
+            Decision::Surrender => "Surrender",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Decision::Hit => "Request to add another card",
+            Decision::Stand => "End turn as is",
+            Decision::Double => "Double the wager, then hit once and stand",
+            Decision::Split => "Play each card of the pair as its own hand",
+            Decision::Surrender => "Give up the hand for half of the bet back",
+        }
+    }
+}
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Outcome {
@@ -66,18 +96,60 @@ impl Display for Outcome {
     }
 }
 
+/// The value of a hand, with its aces counted as 1 or 11 as a whole rather than card by card.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct HandValue {
+    /// The best total of the hand, counting one ace as 11 if that doesn't bust it.
+    pub total: usize,
+    /// Whether one of the hand's aces is currently being counted as 11.
+    pub is_soft: bool,
+}
+
+impl HandValue {
+    pub fn from_faces(faces: impl IntoIterator<Item = Face>) -> HandValue {
+        let (hard_total, has_ace) = faces.into_iter().fold((0, false), |(sum, has_ace), face| {
+            (sum + face_val(face), has_ace || face == Face::Ace)
+        });
+        HandValue::from_hard_total(hard_total, has_ace)
+    }
+
+    /// The value of a hand from its total with every ace counted as 1, and whether it holds any aces.
+    pub fn from_hard_total(hard_total: usize, has_ace: bool) -> HandValue {
+        // only one ace can ever be counted as 11, since two would already make 22
+        if has_ace && hard_total + 10 <= BUST_THRESHOLD {
+            HandValue { total: hard_total + 10, is_soft: true }
+        } else {
+            HandValue { total: hard_total, is_soft: false }
+        }
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.total > BUST_THRESHOLD
+    }
+}
+
+impl Display for HandValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_soft {
+            write!(f, "{}/{}", self.total - 10, self.total)
+        } else {
+            write!(f, "{}", self.total)
+        }
+    }
+}
+
 pub fn get_outcome(hand: &Hand) -> Outcome {
-    let sum = hand_val(hand);
-    if sum > BUST_THRESHOLD {
+    let value = hand_val(hand);
+    if value.is_bust() {
         return Outcome::Bust;
     }
-    Outcome::Holding(sum)
+    Outcome::Holding(value.total)
 }
 
-pub fn face_val(sum: usize, face: Face) -> usize {
+/// Hard value of a card; aces are counted as 1 here and promoted to 11 by [`HandValue`].
+pub fn face_val(face: Face) -> usize {
     match face {
-        Face::Ace if sum > 10 => 1,
-        Face::Ace => 11,
+        Face::Ace => 1,
         Face::King | Face::Queen | Face::Jack | Face::Ten => 10,
         Face::Nine => 9,
         Face::Eight => 8,
@@ -90,30 +162,72 @@ pub fn face_val(sum: usize, face: Face) -> usize {
     }
 }
 
-pub fn hand_val(hand: &Hand) -> usize {
-    hand.cards().iter().fold(0, |acc, card| acc + face_val(acc, card.face))
+pub fn hand_val(hand: &Hand) -> HandValue {
+    HandValue::from_faces(hand.cards().iter().map(|card| card.face))
+}
+
+/// Whether the hand is two cards of equal value, which can be split into two hands.
+pub fn is_pair(hand: &Hand) -> bool {
+    match &hand.cards()[..] {
+        [first, second] => face_val(first.face) == face_val(second.face),
+        _ => false,
+    }
+}
+
+/// Whether the hand is a natural: 21 on its first two cards.
+pub fn is_blackjack(hand: &Hand) -> bool {
+    hand.cards().len() == 2 && hand_val(hand).total == BUST_THRESHOLD
 }
 
 pub fn hand_as_str(hand: &Hand) -> String {
-    format!("✋{}🤚 {}", hand, get_outcome(hand).to_string().truecolor(SUM_COLOR.0, SUM_COLOR.1, SUM_COLOR.2))
+    let value = hand_val(hand);
+    let value_str = if value.is_bust() {
+        Outcome::Bust.to_string()
+    } else {
+        value.to_string().truecolor(SUM_COLOR.0, SUM_COLOR.1, SUM_COLOR.2).to_string()
+    };
+    format!("✋{}🤚 {}", hand, value_str)
 }
 
-pub fn round_decimal(decimal: f64, places: usize) -> f64 {
-    (decimal * 10f64.powi(places as i32)).round() / 10f64.powi(places as i32)
+/// Prompts the player to pick one of `options`.
+/// Doubling down is offered as a yes/no question before the rest of the options.
+///
+/// With a `hint`, such as the basic strategy play, the hint is shown before the player chooses,
+/// and the player is told the correct play if they choose something else.
+pub fn prompt_player(options: &[Decision], hint: Option<Decision>) -> Decision {
+    let (hr, hg, hb) = HINT_COLOR;
+
+    if let Some(hint) = hint {
+        println!("{}", format!("Coach: basic strategy says {}.", hint.name().to_lowercase()).truecolor(hr, hg, hb));
+    }
+    let decision = choose_decision(options);
+    if let Some(hint) = hint.filter(|&hint| hint != decision) {
+        println!("{}", format!("Coach: the correct play was {}.", hint.name().to_lowercase()).truecolor(hr, hg, hb));
+    }
+    decision
 }
 
-pub fn prompt_player() -> Decision {
+fn choose_decision(options: &[Decision]) -> Decision {
     let (br, bg, bb) = BG_TEXT_COLOR;
 
+    if options.contains(&Decision::Double) {
+        let is_doubling_down = confirm(&format!("Double down? This doubles the wager but forces you to hit then stand. {}", "(y/n)".truecolor(br, bg, bb)), true)
+            .expect("failed to read from terminal");
+        if is_doubling_down {
+            return Decision::Double;
+        }
+    }
+
     let prefix = "➜ ".yellow().bold().to_string();
-    let hit_opt_string = "Hit".truecolor(br, bg, bb).to_string();
-    let stand_opt_string = "Stand".truecolor(br, bg, bb).to_string();
-    let hit_selected_string = format!(" {}{}", "Hit".yellow(), ": Request to add another card".truecolor(br, bg, bb));
-    let stand_selected_string = format!(" {}{}", "Stand".yellow(), ": End turn as is".truecolor(br, bg, bb));
+    let option_strings: Vec<(String, String, Decision)> = options.iter().filter(|&&decision| decision != Decision::Double).map(|&decision| {
+        let opt_string = decision.name().truecolor(br, bg, bb).to_string();
+        let selected_string = format!(" {}{}", decision.name().yellow(), format!(": {}", decision.description()).truecolor(br, bg, bb));
+        (opt_string, selected_string, decision)
+    }).collect();
 
     'prompting: loop
     {
-        let sel = Select::new(&prefix, vec![(&hit_opt_string, Some(&hit_selected_string), Decision::Hit), (&stand_opt_string, Some(&stand_selected_string), Decision::Stand)])
+        let sel = Select::new(&prefix, option_strings.iter().map(|(opt_string, selected_string, decision)| (opt_string, Some(selected_string), *decision)).collect())
             .padding(1).override_prefix_len(3).aligned().clear_after();
 
         match sel.prompt("Make a decision:")
@@ -122,17 +236,60 @@ pub fn prompt_player() -> Decision {
                 return first_decision;
             }
             Err(e) => {
-                println!("Something went wrong: {}", e.to_string());
+                println!("Something went wrong: {}", e);
                 continue 'prompting;
             }
         }
     }
 }
 
-pub fn prompt_dealer(hand: &Hand, score_to_beat: usize) -> Decision {
-    let sum = hand_val(hand);
-    if sum >= DEALER_STAND_THRESHOLD || sum > score_to_beat {
-        return Decision::Stand;
+/// A card of `face`, taken out of a fresh deck, for setting up a test.
+#[cfg(test)]
+pub(crate) fn test_card(face: Face) -> cardy::card::Card {
+    let mut deck = cardy::deck::Deck::make_decks(1);
+    std::iter::from_fn(|| deck.deal_one())
+        .find(|card| card.face == face)
+        .expect("every deck holds every face")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aces_count_as_one_when_eleven_would_bust() {
+        let value = HandValue::from_faces([Face::Ace, Face::Five, Face::Nine]);
+        assert_eq!(
+            value,
+            HandValue {
+                total: 15,
+                is_soft: false
+            }
+        );
+    }
+
+    #[test]
+    fn only_one_ace_counts_as_eleven() {
+        let value = HandValue::from_faces([Face::Ace, Face::Ace, Face::Nine]);
+        assert_eq!(
+            value,
+            HandValue {
+                total: 21,
+                is_soft: true
+            }
+        );
+    }
+
+    #[test]
+    fn faces_are_worth_ten() {
+        let value = HandValue::from_faces([Face::King, Face::Queen, Face::Two]);
+        assert_eq!(
+            value,
+            HandValue {
+                total: 22,
+                is_soft: false
+            }
+        );
+        assert!(value.is_bust());
     }
-    Decision::Hit
 }
@@ -1,18 +1,31 @@
-use std::cmp::Ordering;
-use std::thread;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::{env, fs, process, thread};
 
+use blackjack::accuracy::AccuracyTracker;
+use blackjack::chart::{HandKind, StrategyChart};
+use blackjack::counting::{
+    decks_remaining, parse_system, BuiltInSystem, CountingSystem, RunningCount,
+};
+use blackjack::dealer::{DealerHands, DealerOddsTable};
+use blackjack::engine::{HandResult, RoundObserver, RoundState, Table};
+use blackjack::error::GameError;
+use blackjack::ev::EvCalculator;
+use blackjack::event::{GameEvent, Seat};
+use blackjack::money::Money;
+use blackjack::rules::{DealerStyle, HoleCard, RuleSet, Soft17, Surrender};
+use blackjack::shoe::ShoeComposition;
+use blackjack::strategy::{PlayerStrategy, View};
 use blackjack::{
-    face_val, get_outcome, hand_as_str, hand_val, prompt_dealer, prompt_player, round_decimal,
-    Decision, Outcome, BG_TEXT_COLOR, DEALER_COLOR, DEALER_STAND_THRESHOLD,
-    DEALING_SIMULATION_TIME, DECK_REPLACEMENT_THRESHOLD, DOUBLE_DOWN_MULTIPLIER, FG_TEXT_COLOR,
-    LIGHT_TEXT, PLAYER_COLOR, STANDARD_NUM_DECKS, SUM_COLOR, WINNINGS_COLOR, WINNINGS_UNIT_STR,
-    WIN_MULTIPLIER,
+    face_val, get_outcome, hand_as_str, hand_val, is_blackjack, prompt_player, Decision, HandValue,
+    BG_TEXT_COLOR, DEALER_COLOR, DEALING_SIMULATION_TIME, FG_TEXT_COLOR, HINT_COLOR, LIGHT_TEXT,
+    PLAYER_COLOR, SUM_COLOR, WINNINGS_COLOR, WINNINGS_UNIT_STR,
 };
-use cardy::{deck::Deck, hand::Hand, holder::Holder};
+use cardy::{face::Face, hand::Hand, holder::Holder};
 use colored::*;
 use console::Term;
+use prediput::confirm;
 use prediput::prompting::{Predicate, Prompter};
-use prediput::{any_key_continue, confirm};
 
 /*
 * treat as a 1-player game
@@ -21,439 +34,1188 @@ Terms:
     Blackjack: Dealt 21 on the first hand
     Stand: A decision where the player stops hitting
     Push: Both parties get their bet back
+    Surrender: After being initially dealt two cards, the player can give up their hand and get half of their bet back. Late surrender is only offered once the house has checked for a blackjack; early surrender is offered before.
     Double down: After being initially dealt two cards, the player can "double down" to hit once and then stand after. They will gain or lose double their original bet depending on the game's outcome.
     Soft Ace: An ace is normally valued at 11, but if that makes a hand exceed 21, it instead is valued at 1.
-*   Splitting: If dealt two cards, treat each card as separate hands (the bet is applied at the same value for both hands). Cannot split after doubling down or splitting once.
+*   Splitting: If dealt a pair, treat each card as separate hands (the bet is applied at the same value for both hands). Pairs can be re-split up to the rule set's max_split_hands, and split aces may only receive one card each unless the rules let them draw.
 
 BEFORE GAME
     1. State 3:2 +(50%) payout for blackjack
-    2. State dealer hits on soft 17 (stands on x >= 18) or hard 17 (stands on x >= 17)
+    2. State whether the dealer hits soft 17 (H17) or stands on all 17s (S17)
     3. Prompt for a bet
 
 DURING GAME
-    1. Create 4 decks, shuffled into one
-    2. Deal 2 cards to house, reveal one
+    1. Fill a shoe with the rule set's decks, shuffled from the game's seed, then place the cut card (and burn a card, if the rules do)
+    2. Deal 2 cards to house, reveal one (or deal just one without a hole card)
     3. Deal 2 cards to player, reveal both
-    4. Check for a blackjack between the player and house.
+    4. Check for a blackjack between the player and house. The house only peeks at its hole card when showing an ace or a ten-value card.
         - If both have a blackjack, immediately end the game with no gain/loss for either party
         - If one has a blackjack, immediately end the game in their favor
-    5. Let the player make a decision (hit, stand, double down)
+        - If the house shows an ace, first offer insurance (up to half the bet, paying 2:1), or even money (1:1) if the player has a blackjack
+    5. Let the player make a decision (hit, stand, double down, split, surrender) for each of their hands
         - If they double down, they must hit once and stand immediately after.
         - If the player busts, immediately end the game (dealer wins)
     6. Reveal the house's second card
+        - Without a hole card, the second card is dealt now instead. A house blackjack then takes the original bet (or every bet, depending on the rule set).
     7. Let the house make a decision (hit, stand)
-        - The house will continue hitting until their sum exceeds a threshold
-            - Hard: stand on 17 or above
-            - Soft: stand on 18 or above
+        - The house will continue hitting until their sum reaches 17
+            - H17: hit a soft 17, but stand on a hard 17 or above
+            - S17: stand on any 17 or above
+            - A "smart" dealer also stands once their sum beats the player's, but only when chosen explicitly
         - If the house busts, the player wins (given they didn't bust first)
     8. Compare the player and house's sums; whoever has the greater sum wins.
-    9. Provide winnings at a 3:2 rate to the player if they win, or take the entire bid if they lose.
+    9. Provide winnings to the player if they win (1:1, or the doubled-down payout on a doubled hand), or take the entire bid if they lose.
 
 AFTER GAME
-    * Use a new shuffled deck if 50% of the existing deck is consumed
+    * Move every card on the table into the discard tray
+    * Shuffle the discards back into the shoe if the cut card came out during the round
 */
 fn main() {
     #[cfg(target_os = "windows")]
     control::set_virtual_terminal(true)
         .expect("failed to set virtual terminal after recognizing windows operating system");
 
-    let mut winnings: f64 = 100.;
-    let soft_terms: (&str, usize) = if DEALER_STAND_THRESHOLD == 18 {
-        ("soft", 18)
-    } else {
-        ("hard", 17)
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, Options::usage());
+            process::exit(2);
+        }
     };
-    let (sr, sg, sb) = SUM_COLOR;
-    let (fr, fg, fb) = FG_TEXT_COLOR;
+    let rules = options.rules;
+
+    if options.is_showing_chart || options.chart_csv_path.is_some() {
+        let chart = StrategyChart::new(&rules);
+        if options.is_showing_chart {
+            announce_rules(&rules);
+            print!("{}", chart);
+        }
+        if let Some(path) = &options.chart_csv_path {
+            if let Err(e) = fs::write(path, chart.to_csv()) {
+                eprintln!("Couldn't write the chart to {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    if options.is_showing_dealer_odds {
+        let shoe = options
+            .shoe
+            .unwrap_or_else(|| ShoeComposition::full(rules.num_decks));
+        match DealerOddsTable::new(&DealerHands::new(&rules), &shoe) {
+            Ok(table) => {
+                announce_rules(&rules);
+                print!("{}", table);
+            }
+            Err(message) => {
+                eprintln!("{}", message);
+                process::exit(2);
+            }
+        }
+        return;
+    }
+    if let Some(query) = &options.ev_query {
+        if let Err(message) = report_ev(&rules, query, options.shoe) {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+        return;
+    }
+
+    let mut winnings = Money::from_units(100);
     let (wr, wg, wb) = WINNINGS_COLOR;
+    let (br, bg, bb) = BG_TEXT_COLOR;
     let term = Term::stdout();
 
     term.show_cursor().unwrap();
 
-    let num_decks: usize = 4;
-    let mut deck = Deck::make_decks(num_decks).shuffled();
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut table = Table::new(rules, seed);
+    let mut player = HumanPlayer {
+        rules,
+        unstaked: Money::ZERO,
+        accuracy: AccuracyTracker::new(StrategyChart::new(&rules)),
+        is_coaching: options.is_coaching,
+    };
+    let mut renderer = Renderer::new(rules);
+    let mut trainer = options.count_display.map(|display| {
+        CountTrainer::new(
+            display,
+            RunningCount::new(options.count_system.clone(), rules.num_decks),
+        )
+    });
 
     loop {
         // 1 - Announce required rules
         term.clear_screen().unwrap();
         println!(
             "Your balance: {}",
-            format!("{}{}", WINNINGS_UNIT_STR, winnings)
-                .as_str()
-                .truecolor(wr, wg, wb)
+            winnings.to_string().as_str().truecolor(wr, wg, wb)
         );
-        println!();
         println!(
             "{}",
-            format!(
-                "The dealer rewards you at {} of your bet as winnings.",
-                format!("+{:.0}%", (WIN_MULTIPLIER * 100.))
-                    .to_string()
-                    .as_str()
-                    .truecolor(wr, wg, wb)
-            )
-            .as_str()
-            .truecolor(fr, fg, fb)
+            format!("Seed {} (replay this game with --seed {})", seed, seed).truecolor(br, bg, bb)
         );
-        println!(
-            "{}",
-            format!(
-                "{} decks are shuffled together, which refreshes when {} of the deck is used.",
-                STANDARD_NUM_DECKS.to_string().as_str().white(),
-                format!("{:.0}%", (DECK_REPLACEMENT_THRESHOLD * 100.)).white()
-            )
-            .truecolor(fr, fg, fb)
-        );
-        println!("{}", format!("The dealer stands at {} 17 (when their sum is {} or above) or if their sum exceeds the player.", soft_terms.0.to_string().as_str().truecolor(sr, sg, sb), soft_terms.1.to_string().as_str().truecolor(sr, sg, sb)).as_str().truecolor(fr, fg, fb));
         println!();
-
-        if deck.dealt_count() as f64
-            >= DECK_REPLACEMENT_THRESHOLD * (deck.undealt_count() + deck.dealt_count()) as f64
-        {
-            deck.reset();
-            deck.shuffle();
-            println!("{}", "Reset and shuffled the deck.".truecolor(fr, fg, fb));
+        announce_rules(&rules);
+        println!();
+        if let Some(trainer) = trainer.as_ref().filter(|trainer| trainer.is_showing_count) {
+            trainer.show(table.shoe().undealt_count());
         }
 
         // Prompt for bet
-        let winnings_pred: Predicate<f64> = Predicate::new(
+        let winnings_pred: Predicate<Money> = Predicate::new(
             "Your bid must be less than your balance!",
             Box::new(move |uinput| *uinput <= winnings),
         );
-        let cent_pred: Predicate<f64> = Predicate::new(
+        let cent_pred: Predicate<Money> = Predicate::new(
             "You must enter at least a cent!",
-            Box::new(|uinput| *uinput >= 0.01),
+            Box::new(|uinput| *uinput >= Money::CENT),
         );
-        let bid_prompter = Prompter::new("Please enter a decimal!")
+        let bid_prompter = Prompter::new("Please enter an amount like 12.50!")
             .pred(cent_pred)
             .pred(winnings_pred);
-        let bet = round_decimal(
-            bid_prompter.prompt(
-                format!("What is your bet? {}", WINNINGS_UNIT_STR.white())
-                    .truecolor(wr, wg, wb)
-                    .to_string()
-                    .as_str(),
-            ),
-            2,
+        let bet = bid_prompter.prompt(
+            format!("What is your bet? {}", WINNINGS_UNIT_STR.white())
+                .truecolor(wr, wg, wb)
+                .to_string()
+                .as_str(),
         );
 
-        let change_in_winnings = play(bet, &mut deck);
+        player.unstaked = winnings - bet;
+        let change_in_winnings = match play(
+            bet,
+            &mut table,
+            &mut player,
+            &mut renderer,
+            trainer.as_mut(),
+        ) {
+            Ok(change) => change,
+            Err(e) => {
+                eprintln!("The round couldn't be finished: {}", e);
+                process::exit(1);
+            }
+        };
         println!(
             "{}\n",
             report_earnings_progression(winnings, change_in_winnings)
         );
 
-        if winnings + change_in_winnings < 0.01 {
+        winnings += change_in_winnings;
+        if winnings < Money::CENT {
             println!(
                 "{}",
-                "You were donated a cent from charity.".truecolor(wr, wg, wb)
+                "You're out of money. Thanks for playing!".truecolor(wr, wg, wb)
             );
+            break;
         }
-        winnings = round_decimal((winnings + change_in_winnings).max(0.01), 2);
-        any_key_continue().unwrap();
+
+        if let Some(trainer) = &mut trainer {
+            if trainer.has_seen_cards && rand::random::<f64>() < QUIZ_CHANCE {
+                trainer.quiz();
+            }
+        }
+
+        let is_playing_on = confirm(
+            &format!("Play another round? {}", "(y/n)".truecolor(br, bg, bb)),
+            true,
+        )
+        .expect("failed to read from terminal");
+        if !is_playing_on {
+            println!("{}", "Thanks for playing!".truecolor(wr, wg, wb));
+            break;
+        }
+    }
+
+    report_accuracy(&player.accuracy);
+    if let Some(trainer) = &trainer {
+        trainer.report();
     }
 }
 
-/// Returns the change (gain or loss) in winnings from the bet
-fn play(bet: f64, deck: &mut Deck) -> f64 {
-    let term = Term::stdout();
-    let mut player_hand = Hand::new();
-    let mut dealer_hand = Hand::new();
+/// How many of the most frequent mistakes to list at the end of a session.
+const MISTAKES_SHOWN: usize = 5;
+
+/// Chance of a count quiz after any round, on top of the quiz at every reshuffle.
+const QUIZ_CHANCE: f64 = 0.25;
+
+/// Settings read from the command line.
+struct Options {
+    rules: RuleSet,
+    /// Seed for every shuffle, which is picked at random when not given.
+    seed: Option<u64>,
+    /// Whether to print the basic strategy chart for the rules instead of playing.
+    is_showing_chart: bool,
+    /// File to write the basic strategy chart to as CSV instead of playing.
+    chart_csv_path: Option<String>,
+    /// Whether to print the chance of each dealer finish by upcard instead of playing.
+    is_showing_dealer_odds: bool,
+    /// A hand to print the exact expected value of each decision on instead of playing.
+    ev_query: Option<EvQuery>,
+    /// The cards left in the shoe for `ev_query` or the dealer's odds, which is every deck less any cards shown when
+    /// not given.
+    shoe: Option<ShoeComposition>,
+    /// Whether to hint the basic strategy play at every decision.
+    is_coaching: bool,
+    /// Whether to run the counting trainer, and whether it shows the count.
+    count_display: Option<CountDisplay>,
+    /// The system the counting trainer counts by.
+    count_system: Arc<dyn CountingSystem>,
+}
+
+/// The player's cards and the dealer's upcard to work out the exact expected value of each decision for.
+struct EvQuery {
+    player_cards: Vec<Face>,
+    upcard: Face,
+}
+
+impl FromStr for EvQuery {
+    type Err = String;
+
+    /// Parses the player's cards and the upcard like `10,6/9`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (player_cards, upcard) = s
+            .split_once('/')
+            .ok_or_else(|| format!("'{}' isn't cards and an upcard like 10,6/9", s))?;
+        Ok(EvQuery {
+            player_cards: player_cards
+                .split(',')
+                .map(parse_face)
+                .collect::<Result<_, _>>()?,
+            upcard: parse_face(upcard)?,
+        })
+    }
+}
+
+/// Reads a card by its rank, like `A`, `7`, `10` or `K`.
+fn parse_face(s: &str) -> Result<Face, String> {
+    match s.trim().to_uppercase().as_str() {
+        "A" => Ok(Face::Ace),
+        "2" => Ok(Face::Two),
+        "3" => Ok(Face::Three),
+        "4" => Ok(Face::Four),
+        "5" => Ok(Face::Five),
+        "6" => Ok(Face::Six),
+        "7" => Ok(Face::Seven),
+        "8" => Ok(Face::Eight),
+        "9" => Ok(Face::Nine),
+        "10" | "T" => Ok(Face::Ten),
+        "J" => Ok(Face::Jack),
+        "Q" => Ok(Face::Queen),
+        "K" => Ok(Face::King),
+        _ => Err(format!("'{}' isn't a card like A, 7, 10 or K", s)),
+    }
+}
+
+/// Whether the counting trainer shows the count or leaves the player to keep it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum CountDisplay {
+    Shown,
+    Hidden,
+}
+
+impl Options {
+    const USAGE: &'static str = "    --seed <n>             shuffle from this seed to replay an earlier game
+    --coach                hint the basic strategy play at every decision
+    --count <show|hide>    keep the count, showing it or quizzing you on it
+    --count-system <name>  count by hi-lo (the default), ko, hi-opt-ii, omega-ii, zen or wong-halves,
+                           or by custom tags for an ace through a ten, like -1,1,1,1,1,1,0,0,0,-1
+    --chart                print the basic strategy chart for the rules and exit
+    --chart-csv <path>     write the basic strategy chart for the rules to a CSV file and exit
+    --dealer-odds          print the chance of the dealer finishing on each total by upcard and exit
+    --ev <cards/upcard>    print the exact expected value of each decision on a hand, like 10,6/9 for 10 and 6
+                           against a 9, and exit
+    --shoe <counts>        the cards left for --ev or --dealer-odds, as counts of aces through tens like
+                           24,24,24,24,24,24,24,24,24,96, or every deck less any cards shown by default";
+
+    fn usage() -> String {
+        format!(
+            "usage: blackjack [options]\n\n{}\n{}",
+            RuleSet::OPTIONS_USAGE,
+            Options::USAGE
+        )
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut rules = RuleSet::default();
+        let mut seed = None;
+        let mut is_showing_chart = false;
+        let mut chart_csv_path = None;
+        let mut is_showing_dealer_odds = false;
+        let mut ev_query = None;
+        let mut shoe = None;
+        let mut is_coaching = false;
+        let mut count_display = None;
+        let mut count_system: Arc<dyn CountingSystem> = Arc::new(BuiltInSystem::HiLo);
+
+        while let Some(arg) = args.next() {
+            if rules.parse_option(&arg, &mut args)? {
+                continue;
+            }
+            match arg.as_str() {
+                "--coach" => is_coaching = true,
+                "--count" => {
+                    count_display = match args.next().as_deref() {
+                        Some("show") => Some(CountDisplay::Shown),
+                        Some("hide") => Some(CountDisplay::Hidden),
+                        _ => return Err("--count expects show or hide".to_string()),
+                    };
+                }
+                "--count-system" => {
+                    count_system = parse_system(&args.next().unwrap_or_default())
+                        .map_err(|e| format!("--count-system: {}", e))?;
+                }
+                "--chart" => is_showing_chart = true,
+                "--chart-csv" => {
+                    chart_csv_path = Some(
+                        args.next()
+                            .ok_or_else(|| "--chart-csv expects a file path".to_string())?,
+                    );
+                }
+                "--dealer-odds" => is_showing_dealer_odds = true,
+                "--ev" => {
+                    ev_query = Some(
+                        args.next()
+                            .unwrap_or_default()
+                            .parse()
+                            .map_err(|e| format!("--ev: {}", e))?,
+                    );
+                }
+                "--shoe" => {
+                    shoe = Some(
+                        args.next()
+                            .unwrap_or_default()
+                            .parse()
+                            .map_err(|e| format!("--shoe: {}", e))?,
+                    );
+                }
+                "--seed" => {
+                    seed = match args.next().map(|n| n.parse()) {
+                        Some(Ok(n)) => Some(n),
+                        _ => return Err("--seed expects a whole number".to_string()),
+                    };
+                }
+                _ => return Err(format!("unknown option '{}'", arg)),
+            }
+        }
+
+        Ok(Options {
+            rules,
+            seed,
+            is_showing_chart,
+            chart_csv_path,
+            is_showing_dealer_odds,
+            ev_query,
+            shoe,
+            is_coaching,
+            count_display,
+            count_system,
+        })
+    }
+}
 
-    let (dr, dg, db) = DEALER_COLOR;
-    let (pr, pg, pb) = PLAYER_COLOR;
+/// Prints the exact expected value of each decision on `query`'s hand, with the cards to come drawn from `shoe`.
+fn report_ev(
+    rules: &RuleSet,
+    query: &EvQuery,
+    shoe: Option<ShoeComposition>,
+) -> Result<(), String> {
+    let (hr, hg, hb) = HINT_COLOR;
+    let (fr, fg, fb) = FG_TEXT_COLOR;
+
+    let value = HandValue::from_faces(query.player_cards.iter().copied());
+    if query.player_cards.len() < 2 || value.is_bust() {
+        return Err("--ev needs a hand of at least two cards that hasn't busted".to_string());
+    }
+    let shoe = match shoe {
+        Some(shoe) => shoe,
+        None => query
+            .player_cards
+            .iter()
+            .chain([&query.upcard])
+            .try_fold(ShoeComposition::full(rules.num_decks), |shoe, &face| {
+                shoe.without(face)
+            })
+            .ok_or_else(|| "the shoe doesn't hold every card shown".to_string())?,
+    };
+    let cell = EvCalculator::new(rules).evaluate(&query.player_cards, query.upcard, &shoe)?;
+
+    let kind = match query.player_cards[..] {
+        [first, second] if face_val(first) == face_val(second) => HandKind::Pair(face_val(first)),
+        _ if value.is_soft => HandKind::Soft(value.total),
+        _ => HandKind::Hard(value.total),
+    };
+    let upcard = match face_val(query.upcard) {
+        1 => "an ace".to_string(),
+        8 => "an 8".to_string(),
+        val => format!("a {}", val),
+    };
+    println!(
+        "{}",
+        format!(
+            "Expected value per unit bet of a {} against {}, drawing from {} cards:",
+            kind,
+            upcard,
+            shoe.total()
+        )
+        .truecolor(fr, fg, fb)
+    );
+
+    let legal: Vec<Decision> = [
+        Decision::Hit,
+        Decision::Stand,
+        Decision::Double,
+        Decision::Split,
+        Decision::Surrender,
+    ]
+    .into_iter()
+    .filter(|&decision| {
+        query.player_cards.len() == 2 || !matches!(decision, Decision::Double | Decision::Surrender)
+    })
+    .filter(|&decision| cell.ev(decision).is_some())
+    .collect();
+    let best = cell.best(&legal);
+    for decision in legal {
+        let ev = cell
+            .ev(decision)
+            .expect("only decisions with a value are listed");
+        let line = format!("  {:<12} {:+.4}", decision.name(), ev);
+        if decision == best {
+            println!("{}", format!("{}  best", line).truecolor(hr, hg, hb));
+        } else {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+fn announce_rules(rules: &RuleSet) {
     let (sr, sg, sb) = SUM_COLOR;
     let (fr, fg, fb) = FG_TEXT_COLOR;
-    let (br, bg, bb) = BG_TEXT_COLOR;
+    let (wr, wg, wb) = WINNINGS_COLOR;
+
+    println!(
+        "{}",
+        format!(
+            "The dealer pays a blackjack at {}, a win at {} and a doubled win at {}.",
+            rules.blackjack_payout.to_string().truecolor(wr, wg, wb),
+            rules.win_payout.to_string().truecolor(wr, wg, wb),
+            rules.double_down_payout.to_string().truecolor(wr, wg, wb)
+        )
+        .as_str()
+        .truecolor(fr, fg, fb)
+    );
+    println!(
+        "{}",
+        format!(
+            "{} decks are shuffled into the shoe, with the cut card placed {} of the way in{}.",
+            rules.num_decks.to_string().as_str().white(),
+            rules.penetration.to_string().white(),
+            if rules.burns_card {
+                " and a card burned after each shuffle"
+            } else {
+                ""
+            }
+        )
+        .truecolor(fr, fg, fb)
+    );
+    let soft_17_str = match rules.soft_17 {
+        Soft17::Hit => format!(
+            "The dealer hits {} 17 and stands on {} 17 or above",
+            "soft".truecolor(sr, sg, sb),
+            "hard".truecolor(sr, sg, sb)
+        ),
+        Soft17::Stand => format!(
+            "The dealer stands on {} 17 or above",
+            "any".truecolor(sr, sg, sb)
+        ),
+    };
+    let smart_str = match rules.dealer_style {
+        DealerStyle::Casino => "",
+        DealerStyle::Smart => ", or whenever their sum exceeds yours",
+    };
+    println!(
+        "{}",
+        format!("{}{}.", soft_17_str, smart_str).truecolor(fr, fg, fb)
+    );
+
+    let hole_card_str = match rules.hole_card {
+        HoleCard::Peek => "The dealer peeks for a blackjack when showing an ace or a ten.",
+        HoleCard::NoHoleCard if rules.original_bets_only => {
+            "The dealer takes no hole card; a dealer blackjack only takes your original bet."
+        }
+        HoleCard::NoHoleCard => {
+            "The dealer takes no hole card; a dealer blackjack takes every bet, doubled or split."
+        }
+    };
+    let surrender_str = match rules.surrender {
+        Surrender::NotAllowed => "Surrendering is not allowed.",
+        Surrender::Late => "Late surrender is allowed.",
+        Surrender::Early => "Early surrender is allowed.",
+    };
+    println!(
+        "{}",
+        format!("{} {}", hole_card_str, surrender_str).truecolor(fr, fg, fb)
+    );
+}
+
+/// The player at the terminal.
+struct HumanPlayer {
+    rules: RuleSet,
+    /// The part of the balance not yet at stake in the round, which any further stake must come out of.
+    unstaked: Money,
+    /// Every decision so far, scored against basic strategy.
+    accuracy: AccuracyTracker,
+    /// Whether to hint the basic strategy play before each decision.
+    is_coaching: bool,
+}
 
-    // 2 - Deal to dealer
-    println!("\n{}", "Dealing...".truecolor(fr, fg, fb).reversed());
-    println!();
+impl HumanPlayer {
+    /// The extra stake a decision puts on the table.
+    fn stake(&self, decision: Decision, bet: Money) -> Money {
+        match decision {
+            Decision::Double => bet
+                .checked_mul(self.rules.double_down_multiplier as i64 - 1)
+                .expect("money overflowed"),
+            Decision::Split => bet,
+            _ => Money::ZERO,
+        }
+    }
+}
+
+impl PlayerStrategy for HumanPlayer {
+    fn decide(&mut self, view: &View) -> Decision {
+        // doubling and splitting are only offered when the balance can cover them
+        let legal = view
+            .legal
+            .iter()
+            .copied()
+            .filter(|&decision| self.stake(decision, view.bet) <= self.unstaked)
+            .collect();
+        let view = &View { legal, ..*view };
+        let hint = self
+            .is_coaching
+            .then(|| self.accuracy.chart().best_decision(view));
+        let decision = prompt_player(&view.legal, hint);
+        self.accuracy.record(view, decision);
+        self.unstaked -= self.stake(decision, view.bet);
+        decision
+    }
 
-    for i in 0..2 {
-        // dealer
-        let card_dealt = deck
-            .deal_one()
-            .expect("unexpectedly no cards are remaining in the deck");
-        let hand_sum = hand_val(&dealer_hand);
-        let is_blackjack = hand_sum + face_val(hand_sum, card_dealt.face) == 21;
+    fn insurance(&mut self, _view: &View, max: Money) -> Money {
+        let (br, bg, bb) = BG_TEXT_COLOR;
 
-        let card_dealt = if i == 1 && !is_blackjack {
-            card_dealt.hidden()
+        let max = max.min(self.unstaked);
+        if max < Money::CENT {
+            return Money::ZERO;
+        }
+        println!();
+        let is_insuring = confirm(
+            &format!(
+                "The dealer shows an ace. Take insurance against a dealer blackjack? {}",
+                "(y/n)".truecolor(br, bg, bb)
+            ),
+            false,
+        )
+        .expect("failed to read from terminal");
+        if is_insuring {
+            let insurance = prompt_insurance(max);
+            self.unstaked -= insurance;
+            insurance
         } else {
-            card_dealt
-        }; // deal the second card face down unless it's a blackjack
-        dealer_hand.push_card(card_dealt);
-
-        term.clear_last_lines(1).unwrap();
-        let hand_str = match (i, is_blackjack) {
-            (1, false) => "?".truecolor(sr, sg, sb).to_string(),
-            (1, true) => "BJ".black().to_string(),
-            _ => hand_val(&dealer_hand).to_string(),
-        };
+            Money::ZERO
+        }
+    }
 
-        println!(
-            " {} ✋{}🤚 {}",
-            "Dealer".truecolor(dr, dg, db),
-            dealer_hand,
-            hand_str.as_str().truecolor(sr, sg, sb)
-        );
-        thread::sleep(DEALING_SIMULATION_TIME);
+    fn even_money(&mut self, _view: &View) -> bool {
+        let (br, bg, bb) = BG_TEXT_COLOR;
+
+        println!();
+        confirm(
+            &format!(
+                "The dealer shows an ace. Take even money? This pays your blackjack 1:1 right away. {}",
+                "(y/n)".truecolor(br, bg, bb)
+            ),
+            false,
+        )
+        .expect("failed to read from terminal")
     }
+}
 
-    // 3 - Deal to player
-    println!();
-    for i in 0..2 {
-        // player
-        let card_dealt = deck
-            .deal_one()
-            .expect("unexpectedly no cards are remaining in the deck");
-        let hand_sum = hand_val(&player_hand);
-        let is_blackjack = hand_sum + face_val(hand_sum, card_dealt.face) == 21;
-        player_hand.push_card(card_dealt);
-
-        term.clear_last_lines(1).unwrap();
-        let hand_str = match (i, is_blackjack) {
-            (1, true) => "BJ".black().to_string(),
-            _ => hand_val(&player_hand).to_string(),
-        };
+/// Plays out a round by answering each of its states with `player`'s choices, showing each event as it happens.
+/// Returns the change (gain or loss) in winnings from the bet
+fn play(
+    bet: Money,
+    table: &mut Table,
+    player: &mut dyn PlayerStrategy,
+    renderer: &mut Renderer,
+    trainer: Option<&mut CountTrainer>,
+) -> Result<Money, GameError> {
+    let mut onlooker = Onlooker { renderer, trainer };
+    table.deal(bet)?.play_observed(player, &mut onlooker)
+}
 
-        println!(
-            "    {} ✋{}🤚 {}",
-            "You".truecolor(pr, pg, pb),
-            player_hand,
-            hand_str.as_str().truecolor(sr, sg, sb)
-        );
-        thread::sleep(DEALING_SIMULATION_TIME);
+/// Shows the round on the terminal as it's played and keeps the trainer's count, pausing so the player can follow along.
+struct Onlooker<'a> {
+    renderer: &'a mut Renderer,
+    trainer: Option<&'a mut CountTrainer>,
+}
+
+impl RoundObserver for Onlooker<'_> {
+    fn observe(&mut self, event: &GameEvent) {
+        self.renderer.render(event);
+        if let Some(trainer) = self.trainer.as_deref_mut() {
+            trainer.observe(event);
+        }
     }
 
-    // 4 - Check for blackjacks
-    match (hand_val(&player_hand), hand_val(&dealer_hand)) {
-        (21, 21) => {
-            println!(
+    fn before(&mut self, state: &RoundState) {
+        let (fr, fg, fb) = FG_TEXT_COLOR;
+
+        match state {
+            RoundState::EarlyDecision { .. } => println!(
                 "\n{}",
-                "Both players had blackjacks, so the game is a draw. No bets are recognized."
+                "The dealer hasn't checked for a blackjack yet, so you may surrender early."
                     .truecolor(fr, fg, fb)
-            );
-            return 0.;
+            ),
+            //     6. Reveal the house's second card (or deal it, without a hole card)
+            //     7. Let the house make a decision (hit, stand)
+            RoundState::DealerTurn => thread::sleep(DEALING_SIMULATION_TIME),
+            _ => {}
         }
-        (21, _) => {
-            println!("\n{}", "You got a blackjack and won the game!".green());
-            return round_decimal(bet * WIN_MULTIPLIER, 2);
+    }
+}
+
+/// Keeps the count alongside the game, and quizzes the player on it at every reshuffle and now and then between rounds.
+struct CountTrainer {
+    count: RunningCount,
+    is_showing_count: bool,
+    /// Whether any cards have been counted since the last quiz.
+    has_seen_cards: bool,
+    /// How far off each of the player's answers was.
+    misses: Vec<f64>,
+}
+
+impl CountTrainer {
+    fn new(display: CountDisplay, count: RunningCount) -> CountTrainer {
+        CountTrainer {
+            count,
+            is_showing_count: display == CountDisplay::Shown,
+            has_seen_cards: false,
+            misses: Vec::new(),
         }
-        (_, 21) => {
+    }
+
+    fn observe(&mut self, event: &GameEvent) {
+        // quiz on the shoe just finished, before the count starts over
+        if matches!(event, GameEvent::Reshuffled | GameEvent::DiscardsShuffledIn) {
+            self.quiz();
+        }
+        self.count.observe(event);
+        self.has_seen_cards |= matches!(
+            event,
+            GameEvent::CardDealt {
+                is_face_down: false,
+                ..
+            } | GameEvent::HoleCardRevealed { .. }
+        );
+    }
+
+    fn quiz(&mut self) {
+        let (hr, hg, hb) = HINT_COLOR;
+
+        let answer: f64 = Prompter::new("Please enter a number like -3 or 2.5!").prompt(
+            format!(
+                "\nQuiz: what's the {} running count?",
+                self.count.system().name()
+            )
+            .truecolor(hr, hg, hb)
+            .to_string()
+            .as_str(),
+        );
+        let running = self.count.running();
+        let miss = (answer - running).abs();
+        if miss == 0. {
+            println!("{}", "That's right!".truecolor(hr, hg, hb));
+        } else {
             println!(
-                "\n{}",
-                "The dealer got a blackjack, so you lost the game.".red()
+                "{}",
+                format!("Off by {}; the running count is {:+}.", miss, running)
+                    .truecolor(hr, hg, hb)
             );
-            return round_decimal(-bet, 2);
         }
-        _ => {}
+        self.misses.push(miss);
+        self.has_seen_cards = false;
     }
 
-    println!("\n{}", "Your turn.".truecolor(pr, pg, pb).reversed());
-    let is_doubling_down = confirm(
-        &*format!(
-            "Double down? This doubles the wager but forces you to hit then stand. {}",
-            "(y/n)".truecolor(br, bg, bb)
-        ),
-        true,
-    )
-    .expect("failed to read from terminal");
-
-    //     5. Let the player make decisions (hit, stand, double down)
-    let player_outcome = if is_doubling_down {
-        //         - If they double down, they must hit once and stand immediately after.
-        println!("{}", "You doubled your wager!".bright_red().bold());
-        thread::sleep(DEALING_SIMULATION_TIME);
-        let first_turn_outcome = simulate_turn(deck, &mut player_hand, Decision::Hit);
-        thread::sleep(DEALING_SIMULATION_TIME);
-
-        if first_turn_outcome == Outcome::Bust {
-            first_turn_outcome
+    fn show(&self, undealt_cards: usize) {
+        let (hr, hg, hb) = HINT_COLOR;
+        let system = self.count.system();
+
+        // an unbalanced count is bet by its running count alone
+        let true_count_str = if system.is_balanced() {
+            format!(", true count {:+.1}", self.count.true_count(undealt_cards))
         } else {
-            // don't play the second turn if the first one is a bust
-            let second_turn_outcome = simulate_turn(deck, &mut player_hand, Decision::Stand);
-            thread::sleep(DEALING_SIMULATION_TIME);
-            second_turn_outcome
+            String::new()
+        };
+        println!(
+            "{}",
+            format!(
+                "{} running count {:+}{} with {:.1} decks left",
+                system.name(),
+                self.count.running(),
+                true_count_str,
+                decks_remaining(undealt_cards)
+            )
+            .truecolor(hr, hg, hb)
+        );
+    }
+
+    /// Prints how well the player kept the count over the session.
+    fn report(&self) {
+        let (fr, fg, fb) = FG_TEXT_COLOR;
+        if self.misses.is_empty() {
+            return;
         }
-    } else {
-        'hitting: loop {
-            let resp = prompt_player();
-            let outcome = simulate_turn(deck, &mut player_hand, resp);
+        let num_exact = self.misses.iter().filter(|&&miss| miss == 0.).count();
+        let average_miss = self.misses.iter().sum::<f64>() / self.misses.len() as f64;
+        println!(
+            "{}",
+            format!(
+                "You got the count exactly right on {} of {} quizzes, and were off by {:.1} on average.",
+                num_exact,
+                self.misses.len(),
+                average_miss
+            )
+            .truecolor(fr, fg, fb)
+        );
+    }
+}
+
+/// Shows the game on the terminal by following its events, keeping its own copy of the hands on the table.
+struct Renderer {
+    rules: RuleSet,
+    dealer_hand: Hand,
+    is_hole_card_hidden: bool,
+    /// Each of the player's hands, and whether it was made by splitting.
+    player_hands: Vec<(Hand, bool)>,
+    /// The seat of the last card dealt in the opening deal, which is shown as a line redrawn with each card.
+    opening_seat: Option<Seat>,
+    is_dealing: bool,
+    results: Vec<HandResult>,
+}
 
-            if resp == Decision::Stand || outcome == Outcome::Bust {
-                break 'hitting outcome;
+impl Renderer {
+    fn new(rules: RuleSet) -> Renderer {
+        Renderer {
+            rules,
+            dealer_hand: Hand::new(),
+            is_hole_card_hidden: false,
+            player_hands: vec![(Hand::new(), false)],
+            opening_seat: None,
+            is_dealing: false,
+            results: Vec::new(),
+        }
+    }
+
+    fn render(&mut self, event: &GameEvent) {
+        let (dr, dg, db) = DEALER_COLOR;
+        let (pr, pg, pb) = PLAYER_COLOR;
+        let (fr, fg, fb) = FG_TEXT_COLOR;
+        let (r, g, b) = LIGHT_TEXT;
+
+        match *event {
+            GameEvent::Reshuffled => {
+                println!(
+                    "{}",
+                    "The cut card came out, so the shoe was shuffled.".truecolor(fr, fg, fb)
+                );
+            }
+            GameEvent::DiscardsShuffledIn => {
+                println!(
+                    "{}",
+                    "The shoe ran out, so the discards were shuffled in.".truecolor(fr, fg, fb)
+                );
+            }
+            GameEvent::CardBurned => {
+                println!("{}", "The first card was burned.".truecolor(fr, fg, fb));
+            }
+            // 2 - Deal to dealer; 3 - Deal to player
+            GameEvent::RoundStarted { .. } => {
+                *self = Renderer::new(self.rules);
+                self.is_dealing = true;
+                println!("\n{}", "Dealing...".truecolor(fr, fg, fb).reversed());
+            }
+            GameEvent::CardDealt {
+                seat,
+                card,
+                is_face_down,
+            } => {
+                match seat {
+                    Seat::Dealer => {
+                        self.dealer_hand.push_card(card);
+                        self.is_hole_card_hidden |= is_face_down;
+                    }
+                    Seat::Player(index) => self.player_hands[index].0.push_card(card),
+                }
+
+                if self.is_dealing {
+                    if self.opening_seat == Some(seat) {
+                        Term::stdout().clear_last_lines(1).unwrap();
+                    } else {
+                        println!();
+                    }
+                    self.opening_seat = Some(seat);
+                    self.show_hand(seat);
+                } else if self.hand(seat).cards().len() == 2 {
+                    match seat {
+                        Seat::Dealer => self.show_hand(seat),
+                        Seat::Player(_) => println!(
+                            "   {} {}",
+                            "DEAL".truecolor(pr, pg, pb),
+                            hand_as_str(self.hand(seat))
+                        ),
+                    }
+                } else {
+                    println!("    {} {}", "HIT".yellow(), hand_as_str(self.hand(seat)));
+                }
+                thread::sleep(DEALING_SIMULATION_TIME);
+            }
+            GameEvent::EvenMoneyTaken | GameEvent::Insured { .. } => {}
+            // 4 - The dealer peeks at their hole card for a blackjack when showing an ace or a ten-value card
+            GameEvent::DealerPeeked { has_blackjack } => {
+                self.is_dealing = false;
+                println!(
+                    "\n{}",
+                    "The dealer peeks at their hole card for a blackjack...".truecolor(fr, fg, fb)
+                );
+                thread::sleep(DEALING_SIMULATION_TIME);
+                if !has_blackjack {
+                    println!(
+                        "{}",
+                        "The dealer doesn't have a blackjack.".truecolor(fr, fg, fb)
+                    );
+                }
+            }
+            GameEvent::HoleCardRevealed { card } => {
+                self.dealer_hand.cards.pop();
+                self.dealer_hand.push_card(card);
+                self.is_hole_card_hidden = false;
+                self.show_hand(Seat::Dealer);
+                thread::sleep(DEALING_SIMULATION_TIME);
+            }
+            GameEvent::InsuranceSettled { change } => {
+                if change.is_positive() {
+                    println!(
+                        "{}",
+                        format!(
+                            "The dealer has a blackjack, so your insurance pays out {}.",
+                            self.rules.insurance_payout
+                        )
+                        .green()
+                    );
+                } else {
+                    println!(
+                        "{}",
+                        "The dealer doesn't have a blackjack, so your insurance is lost.".red()
+                    );
+                }
+            }
+            GameEvent::TurnStarted {
+                seat: Seat::Player(index),
+            } => {
+                self.is_dealing = false;
+                if index == 0 {
+                    println!("\n{}", "Your turn.".truecolor(pr, pg, pb).reversed());
+                }
+                if self.player_hands.len() > 1 {
+                    println!(
+                        "\n{}",
+                        format!("Hand {} of {}", index + 1, self.player_hands.len())
+                            .truecolor(pr, pg, pb)
+                    );
+                }
             }
+            GameEvent::TurnStarted { seat: Seat::Dealer } => {
+                println!("\n{}", "Dealer's turn.".truecolor(dr, dg, db).reversed());
+            }
+            //         - If they double down, they must hit once and stand immediately after.
+            GameEvent::Doubled { .. } => {
+                println!("{}", "You doubled your wager!".bright_red().bold());
+                thread::sleep(DEALING_SIMULATION_TIME);
+            }
+            GameEvent::Split { hand, num_hands } => {
+                let card = self.player_hands[hand]
+                    .0
+                    .cards
+                    .pop()
+                    .expect("split hand unexpectedly has no cards");
+                self.player_hands[hand].1 = true;
+
+                let mut split_hand = Hand::new();
+                split_hand.push_card(card);
+                self.player_hands.insert(hand + 1, (split_hand, true));
+                println!(
+                    "{}",
+                    format!("You split your pair into {} hands.", num_hands).truecolor(fr, fg, fb)
+                );
+            }
+            GameEvent::Surrendered { .. } => {
+                println!(
+                    "{}",
+                    "You surrendered your hand for half of your bet back.".truecolor(fr, fg, fb)
+                );
+            }
+            GameEvent::Stood { seat, .. } => {
+                println!(
+                    "  {} {}",
+                    "STAND".truecolor(r, g, b),
+                    hand_as_str(self.hand(seat))
+                );
+                if let Seat::Player(index) = seat {
+                    let (hand, is_split) = &self.player_hands[index];
+                    let is_split_aces = *is_split && hand.cards()[0].face == Face::Ace;
+                    if self.rules.split_aces_one_card && is_split_aces {
+                        println!(
+                            "{}",
+                            "Split aces only receive one card each.".truecolor(fr, fg, fb)
+                        );
+                    }
+                }
+            }
+            //         - If the house busts, the player wins (given they didn't bust first)
+            GameEvent::Busted {
+                seat: Seat::Dealer, ..
+            } => println!("\n{}", "The dealer's hand busted.".green()),
+            GameEvent::Busted { .. } => {}
+            GameEvent::HandSettled { result, .. } => self.results.push(result),
+            GameEvent::RoundOver { .. } => self.show_results(),
         }
-    };
+    }
 
-    //         - If the player busts, immediately end the game (dealer wins)
-    if player_outcome == Outcome::Bust {
-        println!("\n{}", "Your hand busted. You lost.".red());
-        return round_decimal(
-            -bet * if is_doubling_down {
-                DOUBLE_DOWN_MULTIPLIER
-            } else {
-                1.
-            },
-            2,
+    fn hand(&self, seat: Seat) -> &Hand {
+        match seat {
+            Seat::Dealer => &self.dealer_hand,
+            Seat::Player(index) => &self.player_hands[index].0,
+        }
+    }
+
+    fn dealer_has_blackjack(&self) -> bool {
+        !self.is_hole_card_hidden && is_blackjack(&self.dealer_hand)
+    }
+
+    fn show_hand(&self, seat: Seat) {
+        let (dr, dg, db) = DEALER_COLOR;
+        let (pr, pg, pb) = PLAYER_COLOR;
+        let (sr, sg, sb) = SUM_COLOR;
+
+        let label = match seat {
+            Seat::Dealer => format!(" {}", "Dealer".truecolor(dr, dg, db)),
+            Seat::Player(_) => format!("    {}", "You".truecolor(pr, pg, pb)),
+        };
+        let hand = self.hand(seat);
+        let hand_str = if seat == Seat::Dealer && self.is_hole_card_hidden {
+            "?".to_string()
+        } else if is_blackjack(hand) {
+            "BJ".black().to_string()
+        } else {
+            hand_val(hand).to_string()
+        };
+        println!(
+            "{} ✋{}🤚 {}",
+            label,
+            hand,
+            hand_str.as_str().truecolor(sr, sg, sb)
         );
     }
 
-    println!("\n{}", "Dealer's turn.".truecolor(dr, dg, db).reversed());
+    fn show_results(&self) {
+        let (dr, dg, db) = DEALER_COLOR;
+        let (pr, pg, pb) = PLAYER_COLOR;
+        let (fr, fg, fb) = FG_TEXT_COLOR;
 
-    //     6. Reveal the house's second card
-    thread::sleep(DEALING_SIMULATION_TIME);
-    println!(
-        " {} ✋{}🤚 {}",
-        "Dealer".truecolor(dr, dg, db),
-        dealer_hand,
-        "?".truecolor(sr, sg, sb)
-    );
+        //         - If one has a blackjack, immediately end the game in their favor
+        match self.results[..] {
+            [HandResult::EvenMoney] => {
+                println!("\n{}", "You took even money and won the game!".green());
+                return;
+            }
+            [HandResult::Surrendered] => return, // already shown when the player surrendered
+            [HandResult::Push] if self.dealer_has_blackjack() => {
+                println!(
+                    "\n{}",
+                    "Both players had blackjacks, so the game is a draw. No bets are recognized."
+                        .truecolor(fr, fg, fb)
+                );
+                return;
+            }
+            [HandResult::Blackjack] if !self.player_hands[0].1 => {
+                println!("\n{}", "You got a blackjack and won the game!".green());
+                return;
+            }
+            _ => {}
+        }
 
-    let c = dealer_hand
-        .cards
-        .pop()
-        .expect("dealer unexpectedly has no cards after being dealt two");
-    let c = c.revealed();
-    dealer_hand.push_card(c);
+        //         - If the player busts or surrenders, immediately end the game (dealer wins)
+        if self
+            .results
+            .iter()
+            .all(|result| matches!(result, HandResult::Busted | HandResult::Surrendered))
+        {
+            if self.results.contains(&HandResult::Busted) {
+                println!("\n{}", "Your hand busted. You lost.".red());
+            }
+            return;
+        }
 
-    thread::sleep(DEALING_SIMULATION_TIME);
-    term.clear_last_lines(1).unwrap();
-    println!(
-        " {} ✋{}🤚 {}",
-        "Dealer".truecolor(dr, dg, db),
-        dealer_hand,
-        hand_val(&dealer_hand)
-            .to_string()
-            .as_str()
-            .truecolor(sr, sg, sb)
-    );
-    thread::sleep(DEALING_SIMULATION_TIME);
-    //     7. Let the house make a decision (hit, stand)
-    let dealer_outcome = loop {
-        let score_to_beat = hand_val(&player_hand);
-        let resp = prompt_dealer(&dealer_hand, score_to_beat);
-        let outcome = simulate_turn(deck, &mut dealer_hand, resp);
-
-        if resp == Decision::Stand || outcome == Outcome::Bust {
-            break outcome;
+        if self.dealer_has_blackjack() {
+            println!(
+                "\n{}",
+                "The dealer got a blackjack, so you lost the game.".red()
+            );
+            return;
         }
-        thread::sleep(DEALING_SIMULATION_TIME);
-    };
 
-    //         - If the house busts, the player wins (given they didn't bust first)
-    if dealer_outcome == Outcome::Bust {
-        println!("\n{}", "The dealer's hand busted. You won!".green());
-        return round_decimal(
-            bet * WIN_MULTIPLIER
-                * if is_doubling_down {
-                    DOUBLE_DOWN_MULTIPLIER
-                } else {
-                    1.
-                },
-            2,
+        println!("\n{}", "Results".bold());
+        println!(
+            " {} {} {}",
+            "Dealer".truecolor(dr, dg, db),
+            self.dealer_hand,
+            get_outcome(&self.dealer_hand)
         );
+        let is_split = self.player_hands.len() > 1;
+        for (i, (hand, _)) in self.player_hands.iter().enumerate() {
+            let label = if is_split {
+                format!("Hand {}", i + 1)
+            } else {
+                "You".to_string()
+            };
+            println!(
+                "    {} {} {}",
+                label.truecolor(pr, pg, pb),
+                hand,
+                get_outcome(hand)
+            );
+        }
+        println!();
+
+        for (i, result) in self.results.iter().enumerate() {
+            let prefix = if is_split {
+                format!("Hand {}: ", i + 1)
+            } else {
+                String::new()
+            };
+            match result {
+                HandResult::Busted => println!("{}{}", prefix, "Busted!".red()),
+                HandResult::Push => println!("{}Draw!", prefix),
+                HandResult::Blackjack | HandResult::EvenMoney | HandResult::Won => {
+                    println!("{}{}", prefix, "You won!".green())
+                }
+                HandResult::Lost => println!("{}{}", prefix, "You lost!".red()),
+                HandResult::Surrendered => println!("{}Surrendered.", prefix),
+            }
+        }
     }
+}
 
-    //     8. Compare the player and house's sums; whoever has the greater sum wins.
-    println!("\n{}", "Results".bold());
-    println!(
-        " {} {} {}",
-        "Dealer".truecolor(dr, dg, db),
-        dealer_hand,
-        dealer_outcome
+/// Prompts for an insurance bet of up to `max_insurance`, which is half the bet or whatever's left of the balance.
+fn prompt_insurance(max_insurance: Money) -> Money {
+    let (wr, wg, wb) = WINNINGS_COLOR;
+
+    let max_pred: Predicate<Money> = Predicate::new(
+        "Insurance can be at most half of your bet, and no more than your balance!",
+        Box::new(move |uinput| *uinput <= max_insurance),
     );
+    let cent_pred: Predicate<Money> = Predicate::new(
+        "You must enter at least a cent!",
+        Box::new(|uinput| *uinput >= Money::CENT),
+    );
+    let insurance_prompter = Prompter::new("Please enter an amount like 12.50!")
+        .pred(cent_pred)
+        .pred(max_pred);
+    insurance_prompter.prompt(
+        format!(
+            "How much insurance? (up to {}) {}",
+            max_insurance, WINNINGS_UNIT_STR
+        )
+        .truecolor(wr, wg, wb)
+        .to_string()
+        .as_str(),
+    )
+}
+
+/// Prints how closely the player's decisions followed basic strategy over the session.
+fn report_accuracy(accuracy: &AccuracyTracker) {
+    let (fr, fg, fb) = FG_TEXT_COLOR;
+    let (wr, wg, wb) = WINNINGS_COLOR;
+    let ev_str = |ev: f64| Money::from_cents((ev * 100.).round() as i64).to_string();
+
+    let Some(percent) = accuracy.accuracy() else {
+        return;
+    };
     println!(
-        "    {} {} {}",
-        "You".truecolor(pr, pg, pb),
-        player_hand,
-        player_outcome
+        "\n{}",
+        format!(
+            "You matched basic strategy on {} of {} decisions.",
+            format!("{:.1}%", percent).white(),
+            accuracy.records().len()
+        )
+        .truecolor(fr, fg, fb)
     );
-    println!();
-
-    //     8. Compare the player and house's sums; whoever has the greater sum wins.
-    //     9. Provide winnings at a 3:2 (3/5) rate to the player if they win, or take the entire bid if they lose.
-    let change = match player_outcome.cmp(&dealer_outcome) {
-        Ordering::Equal => {
-            println!("Draw!");
-            0.
-        }
-        Ordering::Greater => {
-            println!("{}", "You won!".green());
-            bet * WIN_MULTIPLIER
-                * if is_doubling_down {
-                    DOUBLE_DOWN_MULTIPLIER
-                } else {
-                    1.
-                }
-        }
-        Ordering::Less => {
-            println!("{}", "You lost!".red());
-            -bet * if is_doubling_down {
-                DOUBLE_DOWN_MULTIPLIER
-            } else {
-                1.
-            }
+
+    let mistakes = accuracy.mistakes();
+    if !mistakes.is_empty() {
+        println!("{}", "Most frequent mistakes:".truecolor(fr, fg, fb));
+        for mistake in mistakes.iter().take(MISTAKES_SHOWN) {
+            println!(
+                "  {}",
+                format!(
+                    "{}x {}: {} instead of {} ({})",
+                    mistake.count,
+                    mistake.hand_kind,
+                    mistake.made.name().to_lowercase(),
+                    mistake.correct.name().to_lowercase(),
+                    ev_str(-mistake.ev_cost).as_str().red()
+                )
+                .truecolor(fr, fg, fb)
+            );
         }
-    };
-    round_decimal(change, 2)
+    }
+    println!(
+        "{}",
+        format!(
+            "Expected value given away: {}",
+            ev_str(accuracy.ev_given_away())
+                .as_str()
+                .truecolor(wr, wg, wb)
+        )
+        .truecolor(fr, fg, fb)
+    );
 }
 
-fn report_earnings_progression(balance: f64, change: f64) -> String {
+fn report_earnings_progression(balance: Money, change: Money) -> String {
     let (wr, wg, wb) = WINNINGS_COLOR;
     let (fr, fg, fb) = FG_TEXT_COLOR;
 
-    let change_str = if change > 0. {
-        format!("+ {} ", round_decimal(change.abs(), 2))
-            .as_str()
-            .green()
-            .to_string()
-    } else if change < 0. {
-        format!("- {} ", round_decimal(change.abs(), 2))
-            .as_str()
-            .red()
-            .to_string()
+    let change_str = if change.is_positive() {
+        format!("+ {} ", change.abs()).as_str().green().to_string()
+    } else if change.is_negative() {
+        format!("- {} ", change.abs()).as_str().red().to_string()
     } else {
         String::new()
     };
 
     format!(
         "{} {}➜ {}",
-        format!("{}{}", WINNINGS_UNIT_STR, balance)
-            .as_str()
-            .truecolor(wr, wg, wb),
+        balance.to_string().as_str().truecolor(wr, wg, wb),
         change_str,
-        format!(
-            "{}{}",
-            WINNINGS_UNIT_STR,
-            round_decimal((balance + change).max(0.), 2)
-        )
-        .as_str()
-        .truecolor(wr, wg, wb)
+        (balance + change)
+            .max(Money::ZERO)
+            .to_string()
+            .as_str()
+            .truecolor(wr, wg, wb)
     )
     .as_str()
     .truecolor(fr, fg, fb)
     .to_string()
 }
-
-fn simulate_turn(deck: &mut Deck, hand: &mut Hand, decision: Decision) -> Outcome {
-    match decision {
-        Decision::Hit => {
-            let card_to_deal = deck
-                .deal_one()
-                .expect("unexpectedly no cards were left in the deck");
-            hand.push_card(card_to_deal);
-            let outcome = get_outcome(hand);
-            println!("    {} {}", "HIT".yellow(), hand_as_str(hand));
-            outcome
-        }
-        Decision::Stand => {
-            let outcome = get_outcome(hand);
-            let (r, g, b) = LIGHT_TEXT;
-            println!("  {} {}", "STAND".truecolor(r, g, b), hand_as_str(hand));
-            outcome
-        }
-    }
-}
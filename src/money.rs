@@ -0,0 +1,201 @@
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::WINNINGS_UNIT_STR;
+
+const CENTS_PER_UNIT: i64 = 100;
+
+/// An amount of money kept in whole cents, so that balances never pick up floating-point error.
+/// The operators panic on overflow rather than wrapping; the `checked_` methods return `None` instead.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Debug)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+    /// The smallest amount that can be bet.
+    pub const CENT: Money = Money(1);
+
+    pub const fn from_cents(cents: i64) -> Money {
+        Money(cents)
+    }
+
+    /// An amount of whole dollars (or whatever [`WINNINGS_UNIT_STR`] stands for).
+    pub const fn from_units(units: i64) -> Money {
+        Money(units * CENTS_PER_UNIT)
+    }
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    /// The amount in whole units, for statistics where cents no longer need to be exact.
+    pub fn as_units(self) -> f64 {
+        self.0 as f64 / CENTS_PER_UNIT as f64
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn abs(self) -> Money {
+        Money(self.0.checked_abs().expect("money overflowed"))
+    }
+
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    pub fn checked_mul(self, factor: i64) -> Option<Money> {
+        self.0.checked_mul(factor).map(Money)
+    }
+
+    /// Scales the amount by `numerator / denominator`, rounding toward zero to the cent as casinos do with odd payouts.
+    pub fn checked_mul_ratio(self, numerator: u32, denominator: u32) -> Option<Money> {
+        self.0
+            .checked_mul(numerator as i64)?
+            .checked_div(denominator as i64)
+            .map(Money)
+    }
+
+    /// Half of the amount, rounded toward zero to the cent.
+    pub fn half(self) -> Money {
+        Money(self.0 / 2)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        self.checked_add(other).expect("money overflowed")
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Money) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        self.checked_sub(other).expect("money overflowed")
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, other: Money) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(self.0.checked_neg().expect("money overflowed"))
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl Display for Money {
+    /// Written like `$12.50`, or `-$12.50` when negative.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}{}{}.{:02}",
+            sign,
+            WINNINGS_UNIT_STR,
+            cents / CENTS_PER_UNIT as u64,
+            cents % CENTS_PER_UNIT as u64
+        )
+    }
+}
+
+impl FromStr for Money {
+    type Err = String;
+
+    /// Parses an amount like `12`, `12.5` or `$12.50`, with no more than two decimal places.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, s),
+        };
+        let s = s.strip_prefix(WINNINGS_UNIT_STR).unwrap_or(s);
+
+        let (units, cents) = s.split_once('.').unwrap_or((s, ""));
+        if cents.len() > 2 {
+            return Err(format!("'{}' has more than two decimal places", s));
+        }
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (units.is_empty() && cents.is_empty()) || !is_digits(units) || !is_digits(cents) {
+            return Err(format!("'{}' isn't an amount of money", s));
+        }
+
+        let units: i64 = if units.is_empty() {
+            0
+        } else {
+            units.parse().map_err(|_| format!("'{}' is too large", s))?
+        };
+        let cents: i64 = format!("{:0<2}", cents).parse().unwrap_or(0);
+        units
+            .checked_mul(CENTS_PER_UNIT)
+            .and_then(|total| total.checked_add(cents))
+            .map(|total| Money(sign * total))
+            .ok_or_else(|| format!("'{}' is too large", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_amounts() {
+        assert_eq!("12".parse(), Ok(Money::from_units(12)));
+        assert_eq!("12.5".parse(), Ok(Money::from_cents(1250)));
+        assert_eq!(" 12.05 ".parse(), Ok(Money::from_cents(1205)));
+        assert_eq!(".75".parse(), Ok(Money::from_cents(75)));
+        assert_eq!(
+            format!("{}3", WINNINGS_UNIT_STR).parse(),
+            Ok(Money::from_units(3))
+        );
+        assert_eq!("-2.50".parse(), Ok(-Money::from_cents(250)));
+    }
+
+    #[test]
+    fn rejects_what_isnt_money() {
+        for s in [
+            "",
+            ".",
+            "abc",
+            "1.234",
+            "1.2.3",
+            "1,000",
+            "12x",
+            "99999999999999999999",
+        ] {
+            assert!(s.parse::<Money>().is_err(), "'{}' parsed", s);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::money::Money;
+use crate::HandValue;
+
+/// The lowest total the dealer can ever stand on.
+pub const DEALER_STAND_TOTAL: usize = 17;
+
+/// A payout ratio such as 3:2, kept as whole numbers so that it isn't subject to floating-point error.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Payout {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Payout {
+    /// 1:1, paid on ordinary wins.
+    pub const EVEN: Payout = Payout::new(1, 1);
+    /// 3:2, the traditional blackjack payout.
+    pub const THREE_TO_TWO: Payout = Payout::new(3, 2);
+    /// 6:5, the reduced blackjack payout found at many tables.
+    pub const SIX_TO_FIVE: Payout = Payout::new(6, 5);
+    /// 2:1, paid on insurance.
+    pub const TWO_TO_ONE: Payout = Payout::new(2, 1);
+
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        Payout {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// The winnings paid on `stake` at this ratio, not including the stake itself.
+    /// Odd cents are rounded down, in the house's favor.
+    pub fn apply(&self, stake: Money) -> Money {
+        stake
+            .checked_mul_ratio(self.numerator, self.denominator)
+            .expect("money overflowed")
+    }
+}
+
+impl Display for Payout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for Payout {
+    type Err = String;
+
+    /// Parses a ratio written like `3:2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = s
+            .split_once(':')
+            .ok_or_else(|| format!("'{}' isn't a ratio like 3:2", s))?;
+        let numerator = numerator
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' isn't a whole number", numerator))?;
+        let denominator = denominator
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' isn't a whole number", denominator))?;
+        if denominator == 0 {
+            return Err("a payout can't have a denominator of 0".to_string());
+        }
+        Ok(Payout::new(numerator, denominator))
+    }
+}
+
+/// How far into the shoe the cut card is placed, as the fraction of its cards dealt before the cut card comes out.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Penetration {
+    /// The cut card goes in the same place after every shuffle.
+    Fixed(f64),
+    /// The cut card goes anywhere between `min` and `max` after each shuffle, like a dealer cutting by hand.
+    Random { min: f64, max: f64 },
+}
+
+impl Display for Penetration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Penetration::Fixed(fraction) => write!(f, "{:.0}%", fraction * 100.),
+            Penetration::Random { min, max } => {
+                write!(f, "{:.0}-{:.0}%", min * 100., max * 100.)
+            }
+        }
+    }
+}
+
+impl FromStr for Penetration {
+    type Err = String;
+
+    /// Parses a fraction like `0.75`, or a range to pick from after each shuffle like `0.65-0.85`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_fraction = |part: &str| match part.trim().parse::<f64>() {
+            Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+            _ => Err(format!("'{}' isn't a fraction between 0 and 1", part)),
+        };
+        match s.split_once('-') {
+            Some((min, max)) => {
+                let (min, max) = (parse_fraction(min)?, parse_fraction(max)?);
+                if min > max {
+                    return Err(format!("'{}' has its larger fraction first", s));
+                }
+                Ok(Penetration::Random { min, max })
+            }
+            None => Ok(Penetration::Fixed(parse_fraction(s)?)),
+        }
+    }
+}
+
+/// Rules for when the player may surrender their first two cards.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Surrender {
+    NotAllowed,
+    /// The player may surrender once the dealer has checked for a blackjack.
+    Late,
+    /// The player may also surrender before the dealer checks for a blackjack.
+    Early,
+}
+
+/// How the dealer's second card is dealt.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HoleCard {
+    /// The second card is dealt face down, and the dealer peeks at it for a blackjack when showing an ace or a ten-value card.
+    Peek,
+    /// The second card isn't dealt until the players have acted (European style).
+    NoHoleCard,
+}
+
+/// What the dealer does with a soft 17, such as an ace and a six.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Soft17 {
+    /// The dealer hits a soft 17 but stands on a hard one (H17).
+    Hit,
+    /// The dealer stands on every 17 (S17).
+    Stand,
+}
+
+/// How the dealer decides whether to hit.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DealerStyle {
+    /// Hits until reaching the stand threshold, regardless of the player's hand, like any real casino.
+    Casino,
+    /// Also stands as soon as its total beats the player's best hand. No real casino plays this way.
+    Smart,
+}
+
+/// The casino rules a game is played under.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RuleSet {
+    /// Number of decks shuffled together into the shoe.
+    pub num_decks: usize,
+    /// Where the cut card goes; the shoe is reshuffled after the round it comes out in.
+    pub penetration: Penetration,
+    /// Whether the first card after each shuffle is burned into the discard tray unseen.
+    pub burns_card: bool,
+    /// Whether the dealer hits or stands on a soft 17.
+    pub soft_17: Soft17,
+    /// Whether the dealer looks at the player's hand when deciding to hit.
+    pub dealer_style: DealerStyle,
+    /// Payout on a natural blackjack.
+    pub blackjack_payout: Payout,
+    /// Payout on an ordinary win.
+    pub win_payout: Payout,
+    /// Payout on the whole stake of a doubled hand when it wins.
+    pub double_down_payout: Payout,
+    /// Payout on an insurance bet when the dealer has a blackjack.
+    pub insurance_payout: Payout,
+    /// Value to multiply bet by when doubling down.
+    pub double_down_multiplier: u32,
+    /// Most hands a player can hold at once by splitting and re-splitting pairs.
+    pub max_split_hands: usize,
+    /// Whether each split ace is dealt only one more card before standing.
+    pub split_aces_one_card: bool,
+    /// Whether an ace and a ten-value card on a split hand count as a blackjack instead of an ordinary 21.
+    pub split_blackjack_counts: bool,
+    /// When the player may give up half of their bet rather than play their hand out.
+    pub surrender: Surrender,
+    /// Whether the dealer takes a hole card and peeks at it, or plays European style without one.
+    pub hole_card: HoleCard,
+    /// Without a hole card, whether a dealer blackjack only takes the original bet rather than every doubled and split bet.
+    pub original_bets_only: bool,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            num_decks: 4,
+            penetration: Penetration::Fixed(0.5),
+            burns_card: false,
+            soft_17: Soft17::Hit,
+            dealer_style: DealerStyle::Casino,
+            blackjack_payout: Payout::THREE_TO_TWO,
+            win_payout: Payout::EVEN,
+            double_down_payout: Payout::EVEN,
+            insurance_payout: Payout::TWO_TO_ONE,
+            double_down_multiplier: 2,
+            max_split_hands: 4,
+            split_aces_one_card: true,
+            split_blackjack_counts: false,
+            surrender: Surrender::Late,
+            hole_card: HoleCard::Peek,
+            original_bets_only: true,
+        }
+    }
+}
+
+impl RuleSet {
+    /// Names accepted by [`RuleSet::preset`].
+    pub const PRESET_NAMES: [&'static str; 4] =
+        ["standard", "vegas-strip", "atlantic-city", "european"];
+
+    /// The most decks a shoe can hold, so that its ten-valued cards still fit in a
+    /// [`ShoeComposition`](crate::shoe::ShoeComposition) count.
+    pub const MAX_DECKS: usize = u16::MAX as usize / 16;
+
+    /// The most hands a pair can be split into, which keeps the chart's re-split values from recursing too deep.
+    pub const MAX_SPLIT_HANDS: usize = 8;
+
+    /// Six decks, the dealer stands on all 17s, and late surrender is offered.
+    pub fn vegas_strip() -> Self {
+        RuleSet {
+            num_decks: 6,
+            penetration: Penetration::Fixed(0.75),
+            burns_card: true,
+            soft_17: Soft17::Stand,
+            ..RuleSet::default()
+        }
+    }
+
+    /// Eight decks, the dealer stands on all 17s, and late surrender is offered.
+    pub fn atlantic_city() -> Self {
+        RuleSet {
+            num_decks: 8,
+            penetration: Penetration::Fixed(0.75),
+            burns_card: true,
+            soft_17: Soft17::Stand,
+            ..RuleSet::default()
+        }
+    }
+
+    /// Six decks with no hole card, no surrender, and split hands that can't be re-split.
+    pub fn european() -> Self {
+        RuleSet {
+            num_decks: 6,
+            penetration: Penetration::Fixed(0.75),
+            burns_card: true,
+            soft_17: Soft17::Stand,
+            max_split_hands: 2,
+            surrender: Surrender::NotAllowed,
+            hole_card: HoleCard::NoHoleCard,
+            original_bets_only: true,
+            ..RuleSet::default()
+        }
+    }
+
+    /// Whether the dealer has to draw to a hand of `value`, going by the soft 17 rule alone.
+    pub fn dealer_must_hit(&self, value: HandValue) -> bool {
+        value.total < DEALER_STAND_TOTAL
+            || (value.total == DEALER_STAND_TOTAL && value.is_soft && self.soft_17 == Soft17::Hit)
+    }
+
+    /// Usage lines for the options read by [`RuleSet::parse_option`], for any binary that takes them.
+    pub const OPTIONS_USAGE: &'static str = "\
+options are applied in order, so overrides should come after --rules
+    --rules <name>         play under a preset rule set: standard, vegas-strip, atlantic-city or european
+    --decks <n>            number of decks shuffled together
+    --penetration <p>      fraction of the shoe dealt before the cut card, like 0.75, or a range like 0.65-0.85
+    --burn-card            burn the first card after each shuffle
+    --blackjack-pays <n:d> payout on a natural blackjack, like 3:2 or 6:5
+    --max-split-hands <n>  most hands a pair can be split and re-split into, where 1 turns off splitting
+    --split-aces-draw      let split aces be hit and doubled instead of taking one card each
+    --split-blackjack      count an ace and a ten-value card on a split hand as a blackjack
+    --surrender <kind>     none, late or early
+    --no-hole-card         deal the dealer's second card after the players act
+    --all-bets-lost        without a hole card, let a dealer blackjack take doubled and split bets too
+    --h17                  the dealer hits soft 17
+    --s17                  the dealer stands on all 17s
+    --smart-dealer         let the dealer stand as soon as they beat your best hand";
+
+    /// Applies the command-line option `arg` if it's one of the rule options, taking its value from `args`.
+    /// Returns whether `arg` was a rule option.
+    pub fn parse_option(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<bool, String> {
+        match arg {
+            "--rules" => {
+                let name = args.next().unwrap_or_default();
+                *self = RuleSet::preset(&name).ok_or_else(|| {
+                    format!(
+                        "unknown rule set '{}', expected one of: {}",
+                        name,
+                        RuleSet::PRESET_NAMES.join(", ")
+                    )
+                })?;
+            }
+            "--decks" => {
+                self.num_decks = match args.next().map(|n| n.parse()) {
+                    Some(Ok(n)) if (1..=RuleSet::MAX_DECKS).contains(&n) => n,
+                    _ => {
+                        return Err(format!(
+                            "--decks expects a number from 1 to {}",
+                            RuleSet::MAX_DECKS
+                        ))
+                    }
+                };
+            }
+            "--penetration" => {
+                self.penetration = args
+                    .next()
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(|e| format!("--penetration: {}", e))?;
+            }
+            "--burn-card" => self.burns_card = true,
+            "--blackjack-pays" => {
+                self.blackjack_payout = args
+                    .next()
+                    .unwrap_or_default()
+                    .parse()
+                    .map_err(|e| format!("--blackjack-pays: {}", e))?;
+            }
+            "--max-split-hands" => {
+                self.max_split_hands = match args.next().map(|n| n.parse()) {
+                    Some(Ok(n)) if (1..=RuleSet::MAX_SPLIT_HANDS).contains(&n) => n,
+                    _ => {
+                        return Err(format!(
+                            "--max-split-hands expects a number from 1 to {}",
+                            RuleSet::MAX_SPLIT_HANDS
+                        ))
+                    }
+                };
+            }
+            "--split-aces-draw" => self.split_aces_one_card = false,
+            "--split-blackjack" => self.split_blackjack_counts = true,
+            "--surrender" => {
+                self.surrender = match args.next().as_deref() {
+                    Some("none") => Surrender::NotAllowed,
+                    Some("late") => Surrender::Late,
+                    Some("early") => Surrender::Early,
+                    _ => return Err("--surrender expects none, late or early".to_string()),
+                };
+            }
+            "--no-hole-card" => self.hole_card = HoleCard::NoHoleCard,
+            "--all-bets-lost" => self.original_bets_only = false,
+            "--h17" => self.soft_17 = Soft17::Hit,
+            "--s17" => self.soft_17 = Soft17::Stand,
+            "--smart-dealer" => self.dealer_style = DealerStyle::Smart,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Looks up one of the [`RuleSet::PRESET_NAMES`].
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "standard" => Some(RuleSet::default()),
+            "vegas-strip" => Some(RuleSet::vegas_strip()),
+            "atlantic-city" => Some(RuleSet::atlantic_city()),
+            "european" => Some(RuleSet::european()),
+            _ => None,
+        }
+    }
+}
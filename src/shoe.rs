@@ -0,0 +1,284 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use cardy::{card::Card, deck::Deck, face::Face};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::face_val;
+use crate::rules::{Penetration, RuleSet};
+
+/// The decks a table deals from, along with the discard tray that played cards go into and the cut card that
+/// marks when to reshuffle.
+pub struct Shoe {
+    /// Undealt cards, with the next one to be dealt at the end.
+    cards: Vec<Card>,
+    num_cards: usize,
+    discards: Vec<Card>,
+    /// How many undealt cards are left once the cut card comes out.
+    cut_card_at: usize,
+    is_cut_card_out: bool,
+    penetration: Penetration,
+    burns_card: bool,
+    rng: StdRng,
+}
+
+impl Shoe {
+    /// A shuffled shoe of the rule set's decks, cut and burned by its rules.
+    pub fn new(rules: &RuleSet, rng: StdRng) -> Shoe {
+        let mut deck = Deck::make_decks(rules.num_decks);
+        let cards: Vec<Card> = std::iter::from_fn(|| deck.deal_one()).collect();
+        let mut shoe = Shoe {
+            num_cards: cards.len(),
+            cards,
+            discards: Vec::new(),
+            cut_card_at: 0,
+            is_cut_card_out: false,
+            penetration: rules.penetration,
+            burns_card: rules.burns_card,
+            rng,
+        };
+        shoe.shuffle();
+        shoe
+    }
+
+    /// Deals the next card, or `None` once every card is out of the shoe.
+    pub fn deal(&mut self) -> Option<Card> {
+        let card = self.cards.pop()?;
+        if self.cards.len() <= self.cut_card_at {
+            self.is_cut_card_out = true;
+        }
+        Some(card)
+    }
+
+    /// Puts cards that are done being played into the discard tray.
+    pub fn discard(&mut self, cards: impl IntoIterator<Item = Card>) {
+        self.discards
+            .extend(cards.into_iter().map(|card| card.revealed()));
+    }
+
+    /// Gathers the discard tray back into the shoe, shuffles, places the cut card and burns a card if the rules do.
+    /// Cards still in play when this is called are left out of the shoe until they're discarded.
+    /// Returns whether a card was burned.
+    pub fn shuffle(&mut self) -> bool {
+        self.cards.append(&mut self.discards);
+        self.cards.shuffle(&mut self.rng);
+
+        let penetration = match self.penetration {
+            Penetration::Fixed(fraction) => fraction,
+            Penetration::Random { min, max } => self.rng.gen_range(min..=max),
+        };
+        let num_cards = self.cards.len();
+        self.cut_card_at = num_cards - (num_cards as f64 * penetration).round() as usize;
+        self.is_cut_card_out = false;
+
+        if self.burns_card {
+            if let Some(card) = self.cards.pop() {
+                self.discards.push(card);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Shuffles the discard tray in with whatever cards are left, leaving the cards in play where they are.
+    /// No card is burned, and the cut card stays out so that the whole shoe is shuffled after the round.
+    /// Returns whether there were any discards to shuffle in.
+    pub fn shuffle_in_discards(&mut self) -> bool {
+        if self.discards.is_empty() {
+            return false;
+        }
+        self.cards.append(&mut self.discards);
+        self.cards.shuffle(&mut self.rng);
+        self.is_cut_card_out = true;
+        true
+    }
+
+    /// Whether the cut card has come out, which means the shoe is due to be reshuffled after the current round.
+    pub fn is_cut_card_out(&self) -> bool {
+        self.is_cut_card_out
+    }
+
+    /// Every card the shoe holds when it's full.
+    pub fn num_cards(&self) -> usize {
+        self.num_cards
+    }
+
+    pub fn undealt_count(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn discard_count(&self) -> usize {
+        self.discards.len()
+    }
+
+    /// Cards dealt since the last shuffle, including any burned, whether or not they've been discarded yet.
+    pub fn dealt_count(&self) -> usize {
+        self.num_cards - self.cards.len()
+    }
+}
+
+/// The cards left to be dealt, counted by value.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ShoeComposition {
+    /// Cards of each value from ace to ten.
+    counts: [u16; 10],
+}
+
+impl ShoeComposition {
+    /// Cards of each value from ace to ten.
+    pub fn new(counts: [u16; 10]) -> ShoeComposition {
+        ShoeComposition { counts }
+    }
+
+    /// Every card of `num_decks` full decks.
+    ///
+    /// # Panics
+    /// If `num_decks` is more than [`RuleSet::MAX_DECKS`].
+    pub fn full(num_decks: usize) -> ShoeComposition {
+        assert!(
+            num_decks <= RuleSet::MAX_DECKS,
+            "a shoe holds at most {} decks",
+            RuleSet::MAX_DECKS
+        );
+        let mut counts = [4 * num_decks as u16; 10];
+        // tens, jacks, queens and kings are all worth ten
+        counts[9] *= 4;
+        ShoeComposition { counts }
+    }
+
+    /// Cards of value `val`, from 1 for an ace to 10.
+    pub fn count(&self, val: usize) -> usize {
+        self.counts[val - 1] as usize
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|&count| count as usize).sum()
+    }
+
+    /// The composition once a card of `face`'s value is taken out, or `None` if there isn't one left.
+    pub fn without(&self, face: Face) -> Option<ShoeComposition> {
+        self.without_val(face_val(face))
+    }
+
+    pub(crate) fn without_val(&self, val: usize) -> Option<ShoeComposition> {
+        let mut counts = self.counts;
+        counts[val - 1] = counts[val - 1].checked_sub(1)?;
+        Some(ShoeComposition { counts })
+    }
+
+    /// Each value that could be drawn next, with its chance of being drawn and the composition left after it.
+    pub(crate) fn draws(self) -> impl Iterator<Item = (usize, f64, ShoeComposition)> {
+        let total = self.total() as f64;
+        (1..=10).filter_map(move |val| {
+            let rest = self.without_val(val)?;
+            Some((val, self.count(val) as f64 / total, rest))
+        })
+    }
+
+    /// Draws the lowest cards left onto `hard_total` until it reaches `target`, which takes as many cards as any way of
+    /// getting there. Returns whether there were enough.
+    pub(crate) fn draw_lowest(&mut self, mut hard_total: usize, target: usize) -> bool {
+        while hard_total < target {
+            let Some(val) = (1..=10).find(|&val| self.count(val) > 0) else {
+                return false;
+            };
+            *self = self
+                .without_val(val)
+                .expect("a value with cards left can be drawn");
+            hard_total += val;
+        }
+        true
+    }
+}
+
+impl Display for ShoeComposition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let counts: Vec<String> = self.counts.iter().map(|count| count.to_string()).collect();
+        write!(f, "{}", counts.join(","))
+    }
+}
+
+impl FromStr for ShoeComposition {
+    type Err = String;
+
+    /// Parses ten comma-separated counts for aces through tens, like `24,24,24,24,24,24,24,24,24,96` for six decks.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let counts: Vec<u16> = s
+            .split(',')
+            .map(|count| {
+                count
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' isn't a number of cards", count))
+            })
+            .collect::<Result<_, _>>()?;
+        let counts: [u16; 10] = counts.try_into().map_err(|counts: Vec<u16>| {
+            format!(
+                "expected 10 counts for aces through tens, but got {}",
+                counts.len()
+            )
+        })?;
+        Ok(ShoeComposition::new(counts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn shoe(rules: &RuleSet) -> Shoe {
+        Shoe::new(rules, StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn cut_card_comes_out_at_the_penetration() {
+        let mut shoe = shoe(&RuleSet {
+            num_decks: 1,
+            penetration: Penetration::Fixed(0.75),
+            burns_card: false,
+            ..RuleSet::default()
+        });
+        for _ in 0..38 {
+            shoe.deal();
+        }
+        assert!(!shoe.is_cut_card_out());
+        shoe.deal();
+        assert!(shoe.is_cut_card_out());
+    }
+
+    #[test]
+    fn burned_cards_go_to_the_discard_tray() {
+        let mut shoe = shoe(&RuleSet {
+            burns_card: true,
+            ..RuleSet::default()
+        });
+        assert_eq!(shoe.discard_count(), 1);
+        assert_eq!(shoe.dealt_count(), 1);
+
+        let card = shoe.deal().unwrap();
+        shoe.discard([card]);
+        shoe.shuffle();
+        assert_eq!(shoe.discard_count(), 1);
+        assert_eq!(shoe.undealt_count(), shoe.num_cards() - 1);
+    }
+
+    #[test]
+    fn full_shoe_counts_tens_four_times_over() {
+        let shoe = ShoeComposition::full(6);
+        assert_eq!(shoe.count(1), 24);
+        assert_eq!(shoe.count(10), 96);
+        assert_eq!(shoe.total(), 312);
+    }
+
+    #[test]
+    fn composition_parses_ten_counts() {
+        let shoe: ShoeComposition = "4,4,4,4,4,4,4,4,4,16".parse().unwrap();
+        assert_eq!(shoe, ShoeComposition::full(1));
+        assert!("4,4,4".parse::<ShoeComposition>().is_err());
+        assert!("4,4,4,4,4,4,4,4,4,x".parse::<ShoeComposition>().is_err());
+    }
+}
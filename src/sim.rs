@@ -0,0 +1,357 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+
+use crate::counting::{CountingSystem, RunningCount};
+use crate::engine::Table;
+use crate::error::GameError;
+use crate::money::Money;
+use crate::rules::RuleSet;
+use crate::strategy::{BasicStrategy, MimicDealer, PlayerStrategy};
+
+/// Standard normal quantile for a two-sided 95% confidence interval.
+const Z_95: f64 = 1.96;
+
+/// The bot that plays every hand of a simulation.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SimStrategy {
+    /// Plays by the basic strategy chart for the rules.
+    Basic,
+    /// Draws like the dealer, never doubling, splitting or surrendering.
+    MimicDealer,
+}
+
+impl SimStrategy {
+    /// Names accepted by [`SimStrategy::from_name`].
+    pub const NAMES: [&'static str; 2] = ["basic", "mimic-dealer"];
+
+    pub fn from_name(name: &str) -> Option<SimStrategy> {
+        match name {
+            "basic" => Some(SimStrategy::Basic),
+            "mimic-dealer" => Some(SimStrategy::MimicDealer),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SimStrategy::Basic => "basic",
+            SimStrategy::MimicDealer => "mimic-dealer",
+        }
+    }
+
+    /// A fresh bot playing this strategy under `rules`.
+    pub fn build(&self, rules: &RuleSet) -> Box<dyn PlayerStrategy> {
+        match *self {
+            SimStrategy::Basic => Box::new(BasicStrategy::new(rules)),
+            SimStrategy::MimicDealer => Box::new(MimicDealer::new(*rules)),
+        }
+    }
+}
+
+/// How much is bet at the start of each round of a simulation.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BettingScheme {
+    /// The same bet every round.
+    Flat(Money),
+    /// One `unit` per point of the count, rounded down, betting at least one unit and at most `max_units`.
+    /// A balanced system is bet by its true count, and an unbalanced one by its running count.
+    Spread { unit: Money, max_units: u32 },
+}
+
+impl BettingScheme {
+    /// The bet for the next round, given the count going into it and the cards left in the shoe.
+    pub fn bet(&self, count: &RunningCount, undealt_cards: usize) -> Money {
+        match *self {
+            BettingScheme::Flat(bet) => bet,
+            BettingScheme::Spread { unit, max_units } => {
+                let count = if count.system().is_balanced() {
+                    count.true_count(undealt_cards)
+                } else {
+                    count.running()
+                };
+                let units = (count.floor() as i64).clamp(1, max_units as i64);
+                unit.checked_mul(units).expect("money overflowed")
+            }
+        }
+    }
+}
+
+impl Display for BettingScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            BettingScheme::Flat(bet) => write!(f, "flat bets of {}", bet),
+            BettingScheme::Spread { unit, max_units } => {
+                write!(f, "bets of 1 to {} units of {}", max_units, unit)
+            }
+        }
+    }
+}
+
+impl FromStr for BettingScheme {
+    type Err = String;
+
+    /// Parses `flat:<bet>`, like `flat:10`, or `spread:<unit>:<max units>`, like `spread:10:8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_bet = |bet: &str| match bet.parse::<Money>() {
+            Ok(bet) if bet.is_positive() => Ok(bet),
+            Ok(_) => Err(format!("'{}' isn't a positive bet", bet)),
+            Err(e) => Err(e),
+        };
+        match s.split(':').collect::<Vec<_>>()[..] {
+            ["flat", bet] => Ok(BettingScheme::Flat(parse_bet(bet)?)),
+            ["spread", unit, max_units] => {
+                let max_units = match max_units.parse() {
+                    Ok(max_units) if max_units > 0 => max_units,
+                    _ => return Err(format!("'{}' isn't a positive whole number", max_units)),
+                };
+                Ok(BettingScheme::Spread {
+                    unit: parse_bet(unit)?,
+                    max_units,
+                })
+            }
+            _ => Err(format!(
+                "'{}' is neither flat:<bet> nor spread:<unit>:<max units>",
+                s
+            )),
+        }
+    }
+}
+
+/// Everything that decides how a simulation plays out. The same config always gives the same results.
+#[derive(Clone)]
+pub struct SimConfig {
+    pub rules: RuleSet,
+    /// The master seed, which each thread's seed is derived from by [`thread_seed`].
+    pub seed: u64,
+    pub num_rounds: u64,
+    /// Threads to split the rounds between, each playing at a table with its own shoe.
+    pub num_threads: usize,
+    pub strategy: SimStrategy,
+    pub betting: BettingScheme,
+    /// The count that a [`BettingScheme::Spread`] bets by.
+    pub system: Arc<dyn CountingSystem>,
+}
+
+/// A value estimated by simulation, with the half-width of its 95% confidence interval.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Estimate {
+    pub value: f64,
+    pub margin: f64,
+}
+
+/// Running totals over the rounds of a simulation, from which its statistics are estimated.
+/// Money is kept in whole units, and a round's result is its change in winnings divided by its bet.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct SimStats {
+    pub num_rounds: u64,
+    /// Rounds that ended with the player ahead, counting every hand and insurance bet together.
+    pub num_wins: u64,
+    pub num_pushes: u64,
+    pub num_losses: u64,
+    pub total_bet: f64,
+    pub total_change: f64,
+    sum_sq_bet: f64,
+    sum_sq_change: f64,
+    sum_bet_change: f64,
+    sum_result: f64,
+    sum_sq_result: f64,
+}
+
+impl SimStats {
+    /// Adds a round that was dealt with `bet` and ended with `change` in winnings.
+    pub fn record(&mut self, bet: Money, change: Money) {
+        let (bet_units, change_units) = (bet.as_units(), change.as_units());
+        let result = change_units / bet_units;
+
+        self.num_rounds += 1;
+        if change.is_positive() {
+            self.num_wins += 1;
+        } else if change.is_negative() {
+            self.num_losses += 1;
+        } else {
+            self.num_pushes += 1;
+        }
+        self.total_bet += bet_units;
+        self.total_change += change_units;
+        self.sum_sq_bet += bet_units * bet_units;
+        self.sum_sq_change += change_units * change_units;
+        self.sum_bet_change += bet_units * change_units;
+        self.sum_result += result;
+        self.sum_sq_result += result * result;
+    }
+
+    /// Adds every round recorded in `other`.
+    pub fn merge(&mut self, other: &SimStats) {
+        self.num_rounds += other.num_rounds;
+        self.num_wins += other.num_wins;
+        self.num_pushes += other.num_pushes;
+        self.num_losses += other.num_losses;
+        self.total_bet += other.total_bet;
+        self.total_change += other.total_change;
+        self.sum_sq_bet += other.sum_sq_bet;
+        self.sum_sq_change += other.sum_sq_change;
+        self.sum_bet_change += other.sum_bet_change;
+        self.sum_result += other.sum_result;
+        self.sum_sq_result += other.sum_sq_result;
+    }
+
+    /// The fraction of every bet that the house keeps, which is negative when the player has the edge.
+    /// As a ratio of totals, its interval comes from the delta method.
+    pub fn house_edge(&self) -> Estimate {
+        let n = self.num_rounds as f64;
+        let edge = -self.total_change / self.total_bet;
+        // variance of each round's change about what the edge predicts from its bet
+        let residual_var =
+            (self.sum_sq_change + 2. * edge * self.sum_bet_change + edge * edge * self.sum_sq_bet)
+                / (n - 1.);
+        let mean_bet = self.total_bet / n;
+        Estimate {
+            value: edge,
+            margin: Z_95 * (residual_var / n).sqrt() / mean_bet,
+        }
+    }
+
+    /// The standard deviation of a round's result, in bets.
+    pub fn sd_per_round(&self) -> Estimate {
+        let n = self.num_rounds as f64;
+        let mean = self.sum_result / n;
+        let sd = ((self.sum_sq_result - n * mean * mean) / (n - 1.))
+            .max(0.)
+            .sqrt();
+        Estimate {
+            value: sd,
+            margin: Z_95 * sd / (2. * (n - 1.)).sqrt(),
+        }
+    }
+
+    pub fn win_rate(&self) -> Estimate {
+        self.rate(self.num_wins)
+    }
+
+    pub fn push_rate(&self) -> Estimate {
+        self.rate(self.num_pushes)
+    }
+
+    pub fn loss_rate(&self) -> Estimate {
+        self.rate(self.num_losses)
+    }
+
+    fn rate(&self, count: u64) -> Estimate {
+        let n = self.num_rounds as f64;
+        let p = count as f64 / n;
+        Estimate {
+            value: p,
+            margin: Z_95 * (p * (1. - p) / n).sqrt(),
+        }
+    }
+}
+
+/// The seed for the table of thread `thread_index`, as that many steps along a SplitMix64 sequence started from
+/// `master_seed`. Neighboring seeds come out unrelated, so the threads' shoes don't shuffle alike.
+pub fn thread_seed(master_seed: u64, thread_index: usize) -> u64 {
+    const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    let mut z = master_seed.wrapping_add(GAMMA.wrapping_mul(thread_index as u64 + 1));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Plays `config.num_rounds` rounds with no delays or prompts, split as evenly as possible between
+/// `config.num_threads` threads. Each thread's statistics are merged in thread order, so the results depend only on
+/// the config and never on how the threads were scheduled.
+pub fn simulate(config: &SimConfig) -> Result<SimStats, GameError> {
+    let num_threads = config.num_threads.max(1);
+    let shards: Vec<Result<SimStats, GameError>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..num_threads)
+            .map(|thread_index| {
+                // the first few threads each take one of the rounds left over
+                let num_rounds = config.num_rounds / num_threads as u64
+                    + u64::from((thread_index as u64) < config.num_rounds % num_threads as u64);
+                let seed = thread_seed(config.seed, thread_index);
+                scope.spawn(move || simulate_table(config, seed, num_rounds))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("a simulation thread panicked"))
+            .collect()
+    });
+
+    let mut stats = SimStats::default();
+    for shard in shards {
+        stats.merge(&shard?);
+    }
+    Ok(stats)
+}
+
+/// Plays `num_rounds` rounds at a table of its own, shuffled from `seed`.
+fn simulate_table(config: &SimConfig, seed: u64, num_rounds: u64) -> Result<SimStats, GameError> {
+    let mut table = Table::new(config.rules, seed);
+    let mut player = config.strategy.build(&config.rules);
+    let mut count = RunningCount::new(Arc::clone(&config.system), config.rules.num_decks);
+    let mut stats = SimStats::default();
+
+    for _ in 0..num_rounds {
+        let bet = config.betting.bet(&count, table.shoe().undealt_count());
+        let change = table
+            .deal(bet)?
+            .play_observed(player.as_mut(), &mut count)?;
+        stats.record(bet, change);
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counting::BuiltInSystem;
+
+    fn config(num_threads: usize, betting: BettingScheme) -> SimConfig {
+        SimConfig {
+            rules: RuleSet::default(),
+            seed: 42,
+            num_rounds: 20_000,
+            num_threads,
+            strategy: SimStrategy::Basic,
+            betting,
+            system: Arc::new(BuiltInSystem::HiLo),
+        }
+    }
+
+    #[test]
+    fn same_seed_and_threads_give_the_same_results() {
+        for num_threads in [1, 3] {
+            for betting in [
+                BettingScheme::Flat(Money::from_units(10)),
+                BettingScheme::Spread {
+                    unit: Money::from_units(10),
+                    max_units: 8,
+                },
+            ] {
+                let config = config(num_threads, betting);
+                let stats = simulate(&config).unwrap();
+                assert_eq!(stats, simulate(&config).unwrap());
+                assert_eq!(stats.num_rounds, config.num_rounds);
+            }
+        }
+    }
+
+    #[test]
+    fn betting_schemes_parse() {
+        assert_eq!(
+            "flat:10".parse(),
+            Ok(BettingScheme::Flat(Money::from_units(10)))
+        );
+        assert_eq!(
+            "spread:10:8".parse(),
+            Ok(BettingScheme::Spread {
+                unit: Money::from_units(10),
+                max_units: 8
+            })
+        );
+        assert!("spread:10".parse::<BettingScheme>().is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+use cardy::{face::Face, hand::Hand};
+
+use crate::chart::StrategyChart;
+use crate::money::Money;
+use crate::rules::{DealerStyle, RuleSet};
+use crate::{hand_val, Decision};
+
+/// Everything a seat can see when it has to make a choice.
+pub struct View<'a> {
+    /// The hand being played.
+    pub hand: &'a Hand,
+    pub dealer_upcard: Face,
+    /// The bet placed at the start of the round.
+    pub bet: Money,
+    /// The decisions that may be made right now, which is empty when answering insurance or even money.
+    pub legal: Vec<Decision>,
+    /// Whether the dealer is yet to check their hole card for a blackjack, as for a decision made under early
+    /// surrender.
+    pub is_before_peek: bool,
+    /// The best total still standing on the other side of the table, when it's face up.
+    /// Only the dealer gets to see this, as the player's hands are dealt face up but the dealer's aren't.
+    pub opposing_total: Option<usize>,
+}
+
+/// Something that can sit in a seat and make its choices, whether a person at the terminal, the dealer's drawing rules or a bot.
+pub trait PlayerStrategy {
+    /// Picks one of the `view.legal` decisions.
+    fn decide(&mut self, view: &View) -> Decision;
+
+    /// How much insurance to take, up to `max`, when the dealer shows an ace. Declines by default.
+    fn insurance(&mut self, _view: &View, _max: Money) -> Money {
+        Money::ZERO
+    }
+
+    /// Whether to take even money on a blackjack when the dealer shows an ace. Declines by default.
+    fn even_money(&mut self, _view: &View) -> bool {
+        false
+    }
+}
+
+/// The dealer's seat, which draws by the rule set alone.
+pub struct DealerRules {
+    rules: RuleSet,
+}
+
+impl DealerRules {
+    pub fn new(rules: RuleSet) -> DealerRules {
+        DealerRules { rules }
+    }
+}
+
+impl PlayerStrategy for DealerRules {
+    /// Hits until the soft 17 rule says to stand. `view.opposing_total` is only looked at by a [`DealerStyle::Smart`] dealer.
+    fn decide(&mut self, view: &View) -> Decision {
+        let value = hand_val(view.hand);
+        let beats_player = self.rules.dealer_style == DealerStyle::Smart
+            && view.opposing_total.is_some_and(|total| value.total > total);
+        if !self.rules.dealer_must_hit(value) || beats_player {
+            return Decision::Stand;
+        }
+        Decision::Hit
+    }
+}
+
+/// A bot that plays its hand the way the dealer has to, never doubling, splitting or surrendering.
+pub struct MimicDealer {
+    dealer: DealerRules,
+}
+
+impl MimicDealer {
+    pub fn new(rules: RuleSet) -> MimicDealer {
+        MimicDealer {
+            dealer: DealerRules::new(RuleSet {
+                dealer_style: DealerStyle::Casino,
+                ..rules
+            }),
+        }
+    }
+}
+
+impl PlayerStrategy for MimicDealer {
+    fn decide(&mut self, view: &View) -> Decision {
+        self.dealer.decide(view)
+    }
+}
+
+/// A bot that plays every hand by the basic strategy chart for its rules, and never takes insurance or even money.
+pub struct BasicStrategy {
+    chart: StrategyChart,
+}
+
+impl BasicStrategy {
+    pub fn new(rules: &RuleSet) -> BasicStrategy {
+        BasicStrategy {
+            chart: StrategyChart::new(rules),
+        }
+    }
+}
+
+impl PlayerStrategy for BasicStrategy {
+    fn decide(&mut self, view: &View) -> Decision {
+        self.chart.best_decision(view)
+    }
+}
+
+/// Plays a fixed list of decisions in order, such as to replay a game or set up a test.
+/// Once the script runs out, or when its next decision isn't legal, it stands.
+pub struct Scripted {
+    decisions: VecDeque<Decision>,
+    insurance: Money,
+    is_taking_even_money: bool,
+}
+
+impl Scripted {
+    pub fn new(decisions: impl IntoIterator<Item = Decision>) -> Scripted {
+        Scripted {
+            decisions: decisions.into_iter().collect(),
+            insurance: Money::ZERO,
+            is_taking_even_money: false,
+        }
+    }
+
+    /// Takes up to `insurance` whenever it's offered.
+    pub fn with_insurance(mut self, insurance: Money) -> Scripted {
+        self.insurance = insurance;
+        self
+    }
+
+    /// Takes even money whenever it's offered.
+    pub fn taking_even_money(mut self) -> Scripted {
+        self.is_taking_even_money = true;
+        self
+    }
+}
+
+impl PlayerStrategy for Scripted {
+    fn decide(&mut self, view: &View) -> Decision {
+        match self.decisions.pop_front() {
+            Some(decision) if view.legal.contains(&decision) => decision,
+            _ => Decision::Stand,
+        }
+    }
+
+    fn insurance(&mut self, _view: &View, max: Money) -> Money {
+        self.insurance.min(max)
+    }
+
+    fn even_money(&mut self, _view: &View) -> bool {
+        self.is_taking_even_money
+    }
+}